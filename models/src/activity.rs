@@ -0,0 +1,145 @@
+use accelerometer::vector::F32x3;
+
+use crate::pedometer::{
+    PeakDetector,
+    StepDetector,
+};
+
+const MILLISECONDS_PER_MINUTE: u32 = 60 * 1000;
+
+// 今していること
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Activity {
+    Still,
+    Walking,
+    Running,
+}
+
+// 一定時間ごとに歩数からケイデンス(1分あたりの歩数)を求めて、止まっているか歩いているか走っているかを判定する。
+// 歩数計とは別に山の検出をするので、歩数計の検出方法に関わらず同じ判定になる
+pub struct ActivityClassifier {
+    pub detector: PeakDetector,
+    // 判定に使う時間(ミリ秒)
+    pub window_ms: u32,
+    // 歩いている、走っているとみなす最小のケイデンス
+    pub walking_cadence: i32,
+    pub running_cadence: i32,
+    // 今の判定の時間で数えた歩数と経過時間
+    pub step_count: i32,
+    pub elapsed_ms: u32,
+    pub activity: Activity,
+    pub cadence: i32,
+}
+
+impl Default for ActivityClassifier {
+    fn default() -> Self {
+        ActivityClassifier::new()
+    }
+}
+
+impl ActivityClassifier {
+    pub fn new() -> ActivityClassifier {
+        ActivityClassifier {
+            // ときどきの揺れは一定時間の歩数にするとケイデンスが小さくなって数に入らないので、
+            // 歩き始めを確かめずにすぐ数える
+            detector: PeakDetector {
+                min_regular_steps: 1,
+                ..PeakDetector::new()
+            },
+            window_ms: 6000,
+            walking_cadence: 40,
+            running_cadence: 140,
+            step_count: 0,
+            elapsed_ms: 0,
+            activity: Activity::Still,
+            cadence: 0,
+        }
+    }
+    // 歩数計と同じ正規化した加速度を受け取って、判定が変わったらtrueを返す
+    pub fn update(&mut self, normalized_accel: F32x3, dt_ms: u32) -> bool {
+        self.step_count += self.detector.detect(normalized_accel, dt_ms);
+        self.elapsed_ms += dt_ms;
+
+        if self.elapsed_ms < self.window_ms {
+            return false;
+        }
+
+        let last_activity = self.activity;
+        self.cadence = (self.step_count as u32 * MILLISECONDS_PER_MINUTE / self.elapsed_ms) as i32;
+        self.activity = self.classify(self.cadence);
+        self.step_count = 0;
+        self.elapsed_ms = 0;
+
+        self.activity != last_activity
+    }
+    fn classify(&self, cadence: i32) -> Activity {
+        if cadence >= self.running_cadence {
+            Activity::Running
+        } else if cadence >= self.walking_cadence {
+            Activity::Walking
+        } else {
+            Activity::Still
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::Trace;
+
+    // トレースを再生して、判定した順に返す
+    fn classify(text: &str) -> Vec<(Activity, i32)> {
+        let mut classifier = ActivityClassifier::new();
+        let mut last_timestamp_ms = None;
+        let mut results = Vec::new();
+
+        for sample in Trace::new(text).samples() {
+            let sample = sample.unwrap();
+            let dt_ms = sample.elapsed_ms(last_timestamp_ms);
+            last_timestamp_ms = Some(sample.timestamp_ms);
+
+            let elapsed_ms = classifier.elapsed_ms + dt_ms;
+            classifier.update(sample.accel, dt_ms);
+            if elapsed_ms >= classifier.window_ms {
+                results.push((classifier.activity, classifier.cadence));
+            }
+        }
+
+        results
+    }
+
+    #[test]
+    fn test_classify_synthetic_traces() {
+        for (text, expected) in [
+            (include_str!("../traces/synthetic/walking.csv"), Activity::Walking),
+            (include_str!("../traces/synthetic/slow_walking.csv"), Activity::Walking),
+            (include_str!("../traces/synthetic/running.csv"), Activity::Running),
+            (include_str!("../traces/synthetic/sitting.csv"), Activity::Still),
+            (include_str!("../traces/synthetic/driving.csv"), Activity::Still),
+        ] {
+            let results = classify(text);
+
+            // 最初の判定は歩き始めを含むので、2回目以降を見る
+            assert!(results[1..].iter().all(|(activity, _)| *activity == expected), "{:?}", results);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut classifier = ActivityClassifier::new();
+        classifier.window_ms = 1000;
+
+        // 1秒に3歩で180歩/分
+        for index in 0..10 {
+            let z = if index % 3 == 1 { 1.5 } else { 1.0 };
+            let changed = classifier.update(F32x3::new(0.0, 0.0, z), 100);
+
+            assert_eq!(changed, index == 9);
+        }
+
+        assert_eq!(classifier.activity, Activity::Running);
+        assert_eq!(classifier.cadence, 180);
+        assert_eq!(classifier.step_count, 0);
+    }
+}
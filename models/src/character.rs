@@ -1,4 +1,5 @@
 use crate::{
+    activity::Activity,
     bet::Bet,
     meal::Meal,
     pedometer::Pedometer,
@@ -6,6 +7,73 @@ use crate::{
     shuriken::Shuriken
 };
 
+// 親密度に応じたキャラクターとの関係
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Level {
+    Stranger,
+    Acquaintance,
+    Friend,
+    BestFriend,
+}
+
+// 次のLevelに上がるために必要な親密度
+pub struct Levels {
+    pub thresholds: [i32; 3],
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Levels::new([10, 30, 100])
+    }
+}
+
+impl Levels {
+    pub fn new(thresholds: [i32; 3]) -> Levels {
+        Levels { thresholds }
+    }
+    pub fn level(&self, intimacy: i32) -> Level {
+        let [acquaintance, friend, best_friend] = self.thresholds;
+
+        if intimacy >= best_friend {
+            Level::BestFriend
+        } else if intimacy >= friend {
+            Level::Friend
+        } else if intimacy >= acquaintance {
+            Level::Acquaintance
+        } else {
+            Level::Stranger
+        }
+    }
+}
+
+// 何歩ごとにアイテムを見つけるか
+pub struct Discovery {
+    pub rice_ball_interval: i32,
+    pub shuriken_interval: i32,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Discovery::new(10, 10)
+    }
+}
+
+impl Discovery {
+    pub fn new(rice_ball_interval: i32, shuriken_interval: i32) -> Discovery {
+        Discovery {
+            rice_ball_interval,
+            shuriken_interval,
+        }
+    }
+    // 走っているときは半分の歩数で見つける
+    pub fn intervals(&self, activity: &Activity) -> (i32, i32) {
+        match activity {
+            Activity::Running => ((self.rice_ball_interval / 2).max(1), (self.shuriken_interval / 2).max(1)),
+            _ => (self.rice_ball_interval, self.shuriken_interval),
+        }
+    }
+}
+
 pub struct Character {
     pub intimacy: i32,
 }
@@ -27,6 +95,8 @@ impl Character {
         meal: &mut Meal,
         rice_ball: &mut RiceBall
     ) {
+        // 食事量の分だけ親密度UP
+        self.intimate(meal.amount);
         // 食べた分おにぎりの数を減らす
         rice_ball.amount -= meal.amount;
         // 食事量をリセット
@@ -49,23 +119,28 @@ impl Character {
         // 親密度を足す
         self.intimacy += amount;
     }
+    pub fn level(&self, levels: &Levels) -> Level {
+        levels.level(self.intimacy)
+    }
     pub fn walk(
         pedometer: &Pedometer,
+        discovery: &Discovery,
+        activity: &Activity,
         rice_ball: &mut RiceBall,
         shuriken: &mut Shuriken
     ) {
-        const FREQUENCY_OF_STEPS: i32 = 10;
+        let (rice_ball_interval, shuriken_interval) = discovery.intervals(activity);
 
-        // 歩数計が10歩カウントするごとにおにぎりを1個見つける
-        if pedometer.step_count - FREQUENCY_OF_STEPS >= rice_ball.last_step_count {
+        // 歩数計が決まった歩数をカウントするごとにおにぎりを1個見つける
+        if pedometer.step_count - rice_ball_interval >= rice_ball.last_step_count {
             // 最後に見つけた歩数カウントを記録する
             rice_ball.last_step_count = pedometer.step_count;
             // おにぎりを1個追加する
             rice_ball.amount += 1;
         }
 
-        // 歩数計が10歩カウントするごとに手裏剣を1個見つける
-        if pedometer.step_count - FREQUENCY_OF_STEPS >= shuriken.last_step_count {
+        // 歩数計が決まった歩数をカウントするごとに手裏剣を1個見つける
+        if pedometer.step_count - shuriken_interval >= shuriken.last_step_count {
             // 最後に見つけた歩数カウントを記録する
             shuriken.last_step_count = pedometer.step_count;
             // 手裏剣を1個追加する
@@ -119,17 +194,30 @@ mod tests {
         assert_eq!(character.intimacy, 1);
     }
 
+    #[test]
+    fn test_level() {
+        let levels = Levels::new([10, 30, 100]);
+        let mut character = Character { intimacy: 0 };
+
+        assert_eq!(character.level(&levels), Level::Stranger);
+
+        character.intimacy = 10;
+
+        assert_eq!(character.level(&levels), Level::Acquaintance);
+
+        character.intimacy = 99;
+
+        assert_eq!(character.level(&levels), Level::Friend);
+
+        character.intimacy = 100;
+
+        assert_eq!(character.level(&levels), Level::BestFriend);
+    }
+
     #[test]
     fn test_walk() {
-        let mut pedometer = Pedometer {
-            sample_count: 0,
-            total_composite_accel: 0.0,
-            threshold: 1.5,
-            hysteresis: 0.15,
-            step_count: 0,
-            state: false,
-            last_state: false
-        };
+        let mut pedometer = Pedometer::new();
+        let discovery = Discovery::default();
         let mut rice_ball = RiceBall {
             amount: 0,
             last_step_count: 0
@@ -139,16 +227,24 @@ mod tests {
             last_step_count: 0
         };
 
-        Character::walk(&pedometer, &mut rice_ball, &mut shuriken);
+        Character::walk(&pedometer, &discovery, &Activity::Walking, &mut rice_ball, &mut shuriken);
 
         assert_eq!(rice_ball.amount, 0);
         assert_eq!(rice_ball.last_step_count, 0);
 
         pedometer.step_count = 10;
 
-        Character::walk(&pedometer, &mut rice_ball, &mut shuriken);
+        Character::walk(&pedometer, &discovery, &Activity::Walking, &mut rice_ball, &mut shuriken);
 
         assert_eq!(rice_ball.amount, 1);
         assert_eq!(rice_ball.last_step_count, 10);
+
+        // 走っていると5歩で見つける
+        pedometer.step_count = 15;
+
+        Character::walk(&pedometer, &discovery, &Activity::Running, &mut rice_ball, &mut shuriken);
+
+        assert_eq!(rice_ball.amount, 2);
+        assert_eq!(shuriken.amount, 2);
     }
 }
@@ -0,0 +1,223 @@
+// 2000年1月1日からの経過秒で日時を扱う。RTCの年も2000年からの経過年で数える
+const EPOCH_YEAR: u16 = 2000;
+const SECONDS_PER_MINUTE: u32 = 60;
+const SECONDS_PER_HOUR: u32 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u32 = 24 * SECONDS_PER_HOUR;
+// キャラクターが寝ている時間帯
+const NIGHT_START_HOUR: u8 = 22;
+const NIGHT_END_HOUR: u8 = 7;
+
+// 時刻が分からないときは昼から始める
+pub const DEFAULT_TIME: DateTime = DateTime {
+    year: 2021,
+    month: 1,
+    day: 1,
+    hour: 12,
+    minute: 0,
+    second: 0,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl Default for DateTime {
+    fn default() -> Self {
+        DEFAULT_TIME
+    }
+}
+
+impl DateTime {
+    pub fn from_seconds(seconds: u32) -> DateTime {
+        let mut days = seconds / SECONDS_PER_DAY;
+        let time = seconds % SECONDS_PER_DAY;
+
+        let mut year = EPOCH_YEAR;
+        while days >= days_in_year(year) {
+            days -= days_in_year(year);
+            year += 1;
+        }
+
+        let mut month = 1;
+        while days >= days_in_month(year, month) {
+            days -= days_in_month(year, month);
+            month += 1;
+        }
+
+        DateTime {
+            year,
+            month,
+            day: days as u8 + 1,
+            hour: (time / SECONDS_PER_HOUR) as u8,
+            minute: (time % SECONDS_PER_HOUR / SECONDS_PER_MINUTE) as u8,
+            second: (time % SECONDS_PER_MINUTE) as u8,
+        }
+    }
+    pub fn seconds(&self) -> u32 {
+        let mut days = self.day as u32 - 1;
+        for year in EPOCH_YEAR..self.year {
+            days += days_in_year(year);
+        }
+        for month in 1..self.month {
+            days += days_in_month(self.year, month);
+        }
+
+        days * SECONDS_PER_DAY
+            + self.hour as u32 * SECONDS_PER_HOUR
+            + self.minute as u32 * SECONDS_PER_MINUTE
+            + self.second as u32
+    }
+    pub fn is_same_day(&self, other: &DateTime) -> bool {
+        (self.year, self.month, self.day) == (other.year, other.month, other.day)
+    }
+    pub fn is_night(&self) -> bool {
+        self.hour >= NIGHT_START_HOUR || self.hour < NIGHT_END_HOUR
+    }
+    // 日付を変えずに時を合わせる。23時の次は0時に戻る
+    pub fn add_hours(&self, hours: i32) -> DateTime {
+        DateTime {
+            hour: (self.hour as i32 + hours).rem_euclid(24) as u8,
+            ..*self
+        }
+    }
+    // 時を変えずに分を合わせる。合わせた分の0秒から数える
+    pub fn add_minutes(&self, minutes: i32) -> DateTime {
+        DateTime {
+            minute: (self.minute as i32 + minutes).rem_euclid(60) as u8,
+            second: 0,
+            ..*self
+        }
+    }
+}
+
+// 2000年から2099年までは4で割り切れる年がうるう年になる
+fn days_in_year(year: u16) -> u32 {
+    if year % 4 == 0 { 366 } else { 365 }
+}
+
+fn days_in_month(year: u16, month: u8) -> u32 {
+    match month {
+        2 if year % 4 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// 現在の日時を返す時計
+pub trait Clock {
+    fn now(&mut self) -> DateTime;
+    // 持ち主が合わせた日時から数え直す
+    fn set(&mut self, now: DateTime);
+}
+
+// ホストでのテストやシミュレータ用に、手で進める時計
+pub struct FakeClock {
+    // 動き始めてからの時間。シミュレータは加速度センサのサンプルの時刻にも使う
+    pub milliseconds: u64,
+    // 動き始めたときの日時(2000年からの経過秒)。時計を合わせても動き始めてからの時間は戻さない
+    pub start_seconds: i64,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        FakeClock::new(DEFAULT_TIME)
+    }
+}
+
+impl FakeClock {
+    pub fn new(now: DateTime) -> FakeClock {
+        FakeClock {
+            milliseconds: 0,
+            start_seconds: now.seconds() as i64,
+        }
+    }
+    pub fn advance(&mut self, dt_ms: u32) {
+        self.milliseconds += dt_ms as u64;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&mut self) -> DateTime {
+        DateTime::from_seconds((self.start_seconds + (self.milliseconds / 1000) as i64) as u32)
+    }
+    fn set(&mut self, now: DateTime) {
+        self.start_seconds = now.seconds() as i64 - (self.milliseconds / 1000) as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+        DateTime { year, month, day, hour, minute, second }
+    }
+
+    #[test]
+    fn test_seconds() {
+        assert_eq!(DateTime::from_seconds(0), date_time(2000, 1, 1, 0, 0, 0));
+        assert_eq!(date_time(2000, 1, 2, 0, 0, 1).seconds(), SECONDS_PER_DAY + 1);
+
+        for time in [
+            date_time(2000, 2, 29, 23, 59, 59),
+            date_time(2021, 3, 1, 0, 0, 0),
+            date_time(2024, 12, 31, 12, 34, 56),
+        ] {
+            assert_eq!(DateTime::from_seconds(time.seconds()), time);
+        }
+    }
+
+    #[test]
+    fn test_is_same_day() {
+        let time = date_time(2021, 1, 31, 23, 59, 59);
+
+        assert!(time.is_same_day(&date_time(2021, 1, 31, 0, 0, 0)));
+        assert!(!time.is_same_day(&DateTime::from_seconds(time.seconds() + 1)));
+    }
+
+    #[test]
+    fn test_is_night() {
+        assert!(!date_time(2021, 1, 1, 7, 0, 0).is_night());
+        assert!(!date_time(2021, 1, 1, 21, 59, 59).is_night());
+        assert!(date_time(2021, 1, 1, 22, 0, 0).is_night());
+        assert!(date_time(2021, 1, 1, 6, 59, 59).is_night());
+    }
+
+    #[test]
+    fn test_add_hours_and_minutes() {
+        let time = date_time(2021, 1, 31, 23, 59, 30);
+
+        // 日付や時は変わらずに回る
+        assert_eq!(time.add_hours(1), date_time(2021, 1, 31, 0, 59, 30));
+        assert_eq!(time.add_hours(-24), time);
+        assert_eq!(time.add_minutes(1), date_time(2021, 1, 31, 23, 0, 0));
+        assert_eq!(time.add_minutes(-60), date_time(2021, 1, 31, 23, 59, 0));
+    }
+
+    #[test]
+    fn test_fake_clock() {
+        let mut clock = FakeClock::new(date_time(2021, 12, 31, 23, 59, 59));
+
+        clock.advance(999);
+
+        assert_eq!(clock.now(), date_time(2021, 12, 31, 23, 59, 59));
+
+        clock.advance(1);
+
+        assert_eq!(clock.now(), date_time(2022, 1, 1, 0, 0, 0));
+
+        // 合わせた日時から進む
+        clock.set(date_time(2022, 1, 1, 9, 0, 0));
+        clock.advance(1000);
+
+        assert_eq!(clock.now(), date_time(2022, 1, 1, 9, 0, 1));
+        assert_eq!(clock.milliseconds, 1000 + 1000);
+    }
+}
@@ -0,0 +1,101 @@
+use heapless::{
+    consts::*,
+    spsc::Queue,
+};
+
+use crate::{
+    character::Level,
+    goal::Goal,
+    router::Route,
+};
+
+// 歩いて見つけるアイテム
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Item {
+    RiceBall,
+    Shuriken,
+}
+
+// ゲームの中で起きた出来事。画面の描画とは別に、音やセーブがこれを受け取って動く
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    // 歩いてアイテムを1つ見つけた
+    ItemFound(Item),
+    // 歩数が増えた。増えた歩数を持つ
+    Walked(i32),
+    // おにぎりを食べた。食べた数を持つ
+    Ate(i32),
+    GameWon,
+    GameLost,
+    // 新しいLevelになった
+    LevelUp(Level),
+    RouteChanged(Route),
+    // 今日の目標を達成した
+    GoalReached(Goal),
+    DayChanged,
+    // 時計の時が進んだ
+    HourPassed,
+    // 設定を変えた
+    SettingsChanged,
+}
+
+// 受け取られるまで出来事を溜めておくキュー。
+// 溢れたら古いものから捨てて、新しい出来事を残す
+pub struct Events {
+    pub queue: Queue<Event, U32>,
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        Events::new()
+    }
+}
+
+impl Events {
+    pub fn new() -> Events {
+        Events { queue: Queue::new() }
+    }
+    pub fn emit(&mut self, event: Event) {
+        if let Err(event) = self.queue.enqueue(event) {
+            self.queue.dequeue();
+            let _ = self.queue.enqueue(event);
+        }
+    }
+    // 溜まっている出来事を起きた順に取り出す
+    pub fn drain(&mut self) -> impl Iterator<Item = Event> + '_ {
+        let queue = &mut self.queue;
+        core::iter::from_fn(move || queue.dequeue())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain() {
+        let mut events = Events::new();
+
+        events.emit(Event::GameWon);
+        events.emit(Event::RouteChanged(Route::Home));
+
+        assert_eq!(
+            events.drain().collect::<Vec<_>>(),
+            [Event::GameWon, Event::RouteChanged(Route::Home)]
+        );
+        assert_eq!(events.drain().next(), None);
+    }
+
+    #[test]
+    fn test_emit_overflow() {
+        let mut events = Events::new();
+
+        for steps in 0..40 {
+            events.emit(Event::Walked(steps));
+        }
+
+        let drained = events.drain().collect::<Vec<_>>();
+        assert_eq!(drained.first(), Some(&Event::Walked(40 - drained.len() as i32)));
+        assert_eq!(drained.last(), Some(&Event::Walked(39)));
+    }
+}
@@ -0,0 +1,143 @@
+// ページ単位で消去し、消去済み(0xFF)のビットを0にすることで書き込むフラッシュ
+pub trait Flash {
+    type Error;
+
+    // 消去の単位になるページの大きさ
+    fn page_size(&self) -> usize;
+    // ページの数
+    fn page_count(&self) -> usize;
+    fn read(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), Self::Error>;
+    fn write(&mut self, address: usize, data: &[u8]) -> Result<(), Self::Error>;
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RamFlashError {
+    // 範囲外のアドレス
+    OutOfRange,
+    // 書き込み中に電源が切れた
+    PowerLoss,
+}
+
+// ホストでのテスト用にRAM上でフラッシュを真似る。
+// tear_afterを設定すると、そのバイト数を書き込んだところで電源が切れたことにする
+pub struct RamFlash<const PAGE_SIZE: usize, const PAGE_COUNT: usize> {
+    pub pages: [[u8; PAGE_SIZE]; PAGE_COUNT],
+    pub erase_counts: [u32; PAGE_COUNT],
+    pub tear_after: Option<usize>,
+}
+
+impl<const PAGE_SIZE: usize, const PAGE_COUNT: usize> Default for RamFlash<PAGE_SIZE, PAGE_COUNT> {
+    fn default() -> Self {
+        RamFlash::new()
+    }
+}
+
+impl<const PAGE_SIZE: usize, const PAGE_COUNT: usize> RamFlash<PAGE_SIZE, PAGE_COUNT> {
+    pub fn new() -> RamFlash<PAGE_SIZE, PAGE_COUNT> {
+        RamFlash {
+            pages: [[0xFF; PAGE_SIZE]; PAGE_COUNT],
+            erase_counts: [0; PAGE_COUNT],
+            tear_after: None,
+        }
+    }
+    fn check_range(&self, address: usize, length: usize) -> Result<(), RamFlashError> {
+        if address + length > PAGE_SIZE * PAGE_COUNT {
+            Err(RamFlashError::OutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<const PAGE_SIZE: usize, const PAGE_COUNT: usize> Flash for RamFlash<PAGE_SIZE, PAGE_COUNT> {
+    type Error = RamFlashError;
+
+    fn page_size(&self) -> usize {
+        PAGE_SIZE
+    }
+    fn page_count(&self) -> usize {
+        PAGE_COUNT
+    }
+    fn read(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.check_range(address, buffer.len())?;
+
+        for (offset, byte) in buffer.iter_mut().enumerate() {
+            let position = address + offset;
+            *byte = self.pages[position / PAGE_SIZE][position % PAGE_SIZE];
+        }
+
+        Ok(())
+    }
+    fn write(&mut self, address: usize, data: &[u8]) -> Result<(), Self::Error> {
+        self.check_range(address, data.len())?;
+
+        for (offset, byte) in data.iter().enumerate() {
+            if let Some(remaining) = self.tear_after {
+                if remaining == 0 {
+                    return Err(RamFlashError::PowerLoss);
+                }
+                self.tear_after = Some(remaining - 1);
+            }
+
+            // フラッシュは書き込みでビットを0にすることしかできない
+            let position = address + offset;
+            self.pages[position / PAGE_SIZE][position % PAGE_SIZE] &= *byte;
+        }
+
+        Ok(())
+    }
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error> {
+        if page >= PAGE_COUNT {
+            return Err(RamFlashError::OutOfRange);
+        }
+
+        self.pages[page] = [0xFF; PAGE_SIZE];
+        self.erase_counts[page] += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_only_clears_bits() {
+        let mut flash = RamFlash::<16, 2>::new();
+        let mut buffer = [0; 2];
+
+        flash.write(15, &[0x0F, 0xF0]).unwrap();
+        flash.write(15, &[0x3C, 0xFF]).unwrap();
+        flash.read(15, &mut buffer).unwrap();
+
+        assert_eq!(buffer, [0x0C, 0xF0]);
+
+        flash.erase(0).unwrap();
+        flash.read(15, &mut buffer).unwrap();
+
+        assert_eq!(buffer, [0xFF, 0xF0]);
+        assert_eq!(flash.erase_counts, [1, 0]);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut flash = RamFlash::<16, 2>::new();
+
+        assert_eq!(flash.write(31, &[0, 0]), Err(RamFlashError::OutOfRange));
+        assert_eq!(flash.erase(2), Err(RamFlashError::OutOfRange));
+    }
+
+    #[test]
+    fn test_tear_after() {
+        let mut flash = RamFlash::<16, 1>::new();
+        let mut buffer = [0; 4];
+        flash.tear_after = Some(2);
+
+        assert_eq!(flash.write(0, &[0, 0, 0, 0]), Err(RamFlashError::PowerLoss));
+
+        flash.read(0, &mut buffer).unwrap();
+
+        assert_eq!(buffer, [0, 0, 0xFF, 0xFF]);
+    }
+}
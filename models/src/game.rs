@@ -0,0 +1,1120 @@
+use crate::{
+    activity::{
+        Activity,
+        ActivityClassifier,
+    },
+    bet::Bet,
+    character::{
+        Character,
+        Discovery,
+        Level,
+        Levels,
+    },
+    clock::DateTime,
+    event::{
+        Event,
+        Events,
+        Item,
+    },
+    goal::{
+        Goals,
+        GOAL_STEP_INTERVAL,
+    },
+    input::InputEvent,
+    meal::Meal,
+    navigation::{
+        Direction,
+        Navigation,
+    },
+    needs::{
+        Mood,
+        Needs,
+    },
+    pedometer::{
+        Detector,
+        Pedometer,
+        Sample,
+    },
+    power::PowerManager,
+    profile::{
+        Exercise,
+        Profile,
+    },
+    rice_ball::RiceBall,
+    router::{
+        Route,
+        Router,
+    },
+    scene::{
+        Scene,
+        SceneStack,
+        Shot,
+        Transition,
+    },
+    settings::{
+        SettingItem,
+        Settings,
+    },
+    shuriken::Shuriken,
+    target::{
+        Target,
+        TargetPosition,
+    },
+};
+
+
+// 的が動く間隔(ミリ秒)
+const TARGET_INTERVAL_MS: u32 = 100;
+// 食事やゲームの結果の演出を1場面ずつ見せる時間(ミリ秒)
+const SCENE_DURATION_MS: u32 = 3000;
+
+// 入力や時間経過によって起きたこと
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    // ナビゲーションのフォーカスが移動した
+    Focused(Route),
+    // ルーティングが変わった
+    Routed(Route),
+    // 食事量が変わった
+    MealChanged,
+    // BETが変わった
+    BetChanged,
+    // Homeでキャラクターに触れた
+    Touched,
+    // 食事をしてHomeに戻った
+    Ate,
+    // 手裏剣が的に当たってHomeに戻った
+    GameWon,
+    // 手裏剣が的を外してHomeに戻った
+    GameLost,
+    // 的が動いた
+    TargetMoved,
+    // 歩数が増えた
+    Walked,
+    // Homeにいる間に機嫌が変わった
+    MoodChanged,
+    // 時計の分が進んだ
+    MinutePassed,
+    // 日付が変わって今日の歩数がリセットされた
+    DayChanged,
+    // 今日の歩数の目標を達成してご褒美をもらった
+    GoalReached,
+    // 止まっている、歩いている、走っているの判定が変わった
+    ActivityChanged,
+    // 演出の場面かコマが進んだ
+    SceneChanged,
+    // 演出が終わって元のページに戻った
+    SceneEnded,
+    // 設定が変わった
+    SettingsChanged,
+    // 画面の色が変わったので、画面全体を描き直す
+    ThemeChanged,
+    // 時計を合わせたので、画面全体を描き直す。実機ではRTCも合わせる
+    TimeSet,
+    // 1日の目標歩数を変えたので、進み具合も描き直す
+    GoalChanged,
+}
+
+pub struct Game {
+    pub router: Router,
+    pub navigation: Navigation,
+    pub pedometer: Pedometer,
+    pub classifier: ActivityClassifier,
+    pub character: Character,
+    pub rice_ball: RiceBall,
+    pub meal: Meal,
+    pub bet: Bet,
+    pub shuriken: Shuriken,
+    pub target: Target,
+    // 食事やゲームの結果の演出
+    pub scenes: SceneStack,
+    pub needs: Needs,
+    pub levels: Levels,
+    pub discovery: Discovery,
+    pub goals: Goals,
+    pub profile: Profile,
+    pub settings: Settings,
+    // 今の画面が暗い色で描かれているか
+    pub dark: bool,
+    // 操作が無いときに画面を暗くしたり消したりする
+    pub power: PowerManager,
+    // 今日歩いた距離と消費カロリー
+    pub exercise: Exercise,
+    // 最後に時計から受け取った日時
+    pub time: DateTime,
+    // 今日の始まりの歩数
+    pub day_start_step_count: i32,
+    // 音やセーブに知らせる出来事
+    pub events: Events,
+    pub elapsed_ms: u32,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Game {
+        Game {
+            router: Router::new(Route::Home),
+            navigation: Navigation::new(Route::Home),
+            pedometer: Pedometer::new(),
+            classifier: ActivityClassifier::new(),
+            character: Character::new(),
+            rice_ball: RiceBall::new(),
+            meal: Meal::new(),
+            bet: Bet::new(),
+            shuriken: Shuriken::new(),
+            target: Target::new(),
+            scenes: SceneStack::new(),
+            needs: Needs::new(),
+            levels: Levels::default(),
+            discovery: Discovery::default(),
+            goals: Goals::default(),
+            profile: Profile::default(),
+            settings: Settings::new(),
+            dark: false,
+            power: PowerManager::new(),
+            exercise: Exercise::new(),
+            time: DateTime::default(),
+            day_start_step_count: 0,
+            events: Events::new(),
+            elapsed_ms: 0,
+        }
+    }
+    pub fn handle(&mut self, event: InputEvent) -> Option<Outcome> {
+        // 画面が暗いときや消えているときは、ボタンで画面を点けるだけにする
+        if self.power.wake() {
+            return None;
+        }
+
+        let level = self.level();
+
+        let outcome = match event {
+            // 演出の間はボタンを押すと次の場面に飛ばす
+            _ if self.scenes.is_playing() => self.skip_scene(event),
+            InputEvent::Left => self.navigate(Direction::Left),
+            InputEvent::Right => self.navigate(Direction::Right),
+            InputEvent::Up => self.increase(),
+            InputEvent::Down => self.decrease(),
+            InputEvent::Press => self.press(),
+        };
+
+        if self.level() > level {
+            self.events.emit(Event::LevelUp(self.level()));
+        }
+
+        outcome
+    }
+    pub fn level(&self) -> Level {
+        self.character.level(&self.levels)
+    }
+    // 夜は欲求にかかわらず寝ている。一緒に歩いたり走ったりしている間は喜ぶ
+    pub fn mood(&self) -> Mood {
+        if self.time.is_night() {
+            Mood::Sleep
+        } else if self.activity() != Activity::Still {
+            Mood::Happy
+        } else {
+            self.needs.mood(self.level())
+        }
+    }
+    // 演出に隠れずにHomeが見えているか
+    pub fn is_home_visible(&self) -> bool {
+        self.router.route == Route::Home && !self.scenes.is_playing()
+    }
+    // 設定と時刻から決まる色に画面を合わせる。変わったらtrueを返す
+    pub fn update_theme(&mut self) -> bool {
+        let dark = self.settings.is_dark(&self.time);
+        let changed = dark != self.dark;
+        self.dark = dark;
+        changed
+    }
+    // 設定と違う検出アルゴリズムで数えていたら切り替える
+    pub fn update_detector(&mut self) {
+        if self.pedometer.detector.kind() != self.settings.detector {
+            self.pedometer.detector = Detector::new(self.settings.detector);
+        }
+    }
+    pub fn activity(&self) -> Activity {
+        self.classifier.activity
+    }
+    pub fn daily_step_count(&self) -> i32 {
+        self.pedometer.step_count - self.day_start_step_count
+    }
+    // 次の目標までの進み具合
+    pub fn progress(&self) -> (i32, i32) {
+        self.goals.progress(self.daily_step_count())
+    }
+    // 経過時間を進める
+    pub fn tick(&mut self, dt_ms: u32) -> Option<Outcome> {
+        let mood = self.mood();
+        Needs::tick(&mut self.needs, dt_ms);
+        self.power.tick(dt_ms);
+
+        if self.scenes.is_playing() {
+            return self.scenes.tick(dt_ms).map(scene_outcome);
+        }
+
+        if self.router.route != Route::Game {
+            self.elapsed_ms = 0;
+
+            if self.router.route == Route::Home && self.mood() != mood {
+                return Some(Outcome::MoodChanged);
+            }
+            return None;
+        }
+
+        self.elapsed_ms += dt_ms;
+
+        if self.elapsed_ms < TARGET_INTERVAL_MS {
+            return None;
+        }
+
+        // 的の位置を動かす
+        self.elapsed_ms -= TARGET_INTERVAL_MS;
+        Target::update(&mut self.target);
+        Some(Outcome::TargetMoved)
+    }
+    // 時計の日時を受け取る。日付が変わったら今日の歩数を0から数え直す
+    pub fn update_time(&mut self, now: DateTime) -> Option<Outcome> {
+        let mood = self.mood();
+        let last_time = self.time;
+        self.time = now;
+        self.pedometer.history.advance(&last_time, &now);
+
+        let minute_passed = (now.hour, now.minute) != (last_time.hour, last_time.minute);
+        if now.hour != last_time.hour {
+            self.events.emit(Event::HourPassed);
+        }
+
+        let day_changed = !now.is_same_day(&last_time);
+        if day_changed {
+            self.day_start_step_count = self.pedometer.step_count;
+            self.goals.reset();
+            self.exercise.reset();
+            self.events.emit(Event::DayChanged);
+        }
+        // 夜になって色が変わったら画面全体を描き直す。演出の途中では変えずに、終わってから変える
+        if !self.scenes.is_playing() && self.update_theme() {
+            return Some(Outcome::ThemeChanged);
+        }
+        if day_changed {
+            return Some(Outcome::DayChanged);
+        }
+        if self.is_home_visible() && self.mood() != mood {
+            return Some(Outcome::MoodChanged);
+        }
+        if minute_passed {
+            return Some(Outcome::MinutePassed);
+        }
+
+        None
+    }
+    // 溜まった加速度センサのサンプルから歩数を数えて、歩いた分アイテムを見つける
+    pub fn sample<I>(&mut self, samples: I) -> Option<Outcome>
+    where
+        I: IntoIterator<Item = Sample>,
+    {
+        let last_step_count = self.pedometer.step_count;
+        let last_activity = self.activity();
+
+        for sample in samples {
+            let dt_ms = sample.elapsed_ms(self.pedometer.last_timestamp_ms);
+            let step_count = self.pedometer.step_count;
+            self.classifier.update(sample.accel, dt_ms);
+            Pedometer::push(&mut self.pedometer, sample);
+
+            for _ in step_count..self.pedometer.step_count {
+                self.exercise.add_step(&self.profile, self.classifier.cadence);
+            }
+            self.walk();
+        }
+
+        if self.pedometer.step_count != last_step_count {
+            self.events.emit(Event::Walked(self.pedometer.step_count - last_step_count));
+            self.power.walk(self.pedometer.step_count - last_step_count);
+        }
+
+        // 目標の達成、判定の変化、歩数の順に知らせる。どれも歩数の表示を描き直す
+        if self.pedometer.step_count != last_step_count && self.reward() {
+            Some(Outcome::GoalReached)
+        } else if self.activity() != last_activity {
+            Some(Outcome::ActivityChanged)
+        } else if self.pedometer.step_count != last_step_count {
+            Some(Outcome::Walked)
+        } else {
+            None
+        }
+    }
+    // 歩いた分アイテムを見つける
+    fn walk(&mut self) {
+        let rice_ball_amount = self.rice_ball.amount;
+        let shuriken_amount = self.shuriken.amount;
+
+        Character::walk(
+            &self.pedometer,
+            &self.discovery,
+            &self.classifier.activity,
+            &mut self.rice_ball,
+            &mut self.shuriken,
+        );
+
+        if self.rice_ball.amount > rice_ball_amount {
+            self.events.emit(Event::ItemFound(Item::RiceBall));
+        }
+        if self.shuriken.amount > shuriken_amount {
+            self.events.emit(Event::ItemFound(Item::Shuriken));
+        }
+    }
+    // 今日の目標を達成していたらご褒美をもらう
+    fn reward(&mut self) -> bool {
+        let level = self.level();
+        let mut is_reached = false;
+
+        while let Some(goal) = self.goals.check(self.daily_step_count()) {
+            self.rice_ball.amount += goal.reward.rice_ball;
+            self.shuriken.amount += goal.reward.shuriken;
+            Character::intimate(&mut self.character, goal.reward.intimacy);
+            self.events.emit(Event::GoalReached(goal));
+            is_reached = true;
+        }
+
+        if self.level() > level {
+            self.events.emit(Event::LevelUp(self.level()));
+        }
+
+        is_reached
+    }
+    fn navigate(&mut self, direction: Direction) -> Option<Outcome> {
+        // Game中はナビゲーションを動かさない
+        if self.router.route == Route::Game {
+            return None;
+        }
+
+        Navigation::update(&mut self.navigation, direction);
+        Some(Outcome::Focused(self.navigation.focus))
+    }
+    fn increase(&mut self) -> Option<Outcome> {
+        match self.router.route {
+            Route::Meal => {
+                // 食事の量を増やす
+                Meal::increase(&mut self.meal, self.rice_ball.amount);
+                Some(Outcome::MealChanged)
+            }
+            Route::Play => {
+                // 掛け金の量を増やす
+                Bet::increase(&mut self.bet, self.shuriken.amount);
+                Some(Outcome::BetChanged)
+            }
+            // 選んでいる設定を切り替える
+            Route::Stats => match self.settings.selected {
+                SettingItem::Hour => self.set_time(self.time.add_hours(1)),
+                SettingItem::Minute => self.set_time(self.time.add_minutes(1)),
+                SettingItem::Goal => self.change_goal(GOAL_STEP_INTERVAL),
+                SettingItem::Height | SettingItem::Weight | SettingItem::Stride => self.change_profile(1),
+                _ => {
+                    self.settings.increase();
+                    self.change_settings()
+                }
+            },
+            Route::Home | Route::Game => None,
+        }
+    }
+    fn decrease(&mut self) -> Option<Outcome> {
+        match self.router.route {
+            Route::Meal => {
+                // 食事の量を減らす
+                Meal::decrease(&mut self.meal);
+                Some(Outcome::MealChanged)
+            }
+            Route::Play => {
+                // 掛け金の量を減らす
+                Bet::decrease(&mut self.bet);
+                Some(Outcome::BetChanged)
+            }
+            Route::Stats => match self.settings.selected {
+                SettingItem::Hour => self.set_time(self.time.add_hours(-1)),
+                SettingItem::Minute => self.set_time(self.time.add_minutes(-1)),
+                SettingItem::Goal => self.change_goal(-GOAL_STEP_INTERVAL),
+                SettingItem::Height | SettingItem::Weight | SettingItem::Stride => self.change_profile(-1),
+                _ => {
+                    self.settings.decrease();
+                    self.change_settings()
+                }
+            },
+            Route::Home | Route::Game => None,
+        }
+    }
+    // 設定を変えたらセーブして、画面の色が変わったら全体を描き直す
+    fn change_settings(&mut self) -> Option<Outcome> {
+        self.update_detector();
+        self.events.emit(Event::SettingsChanged);
+        if self.update_theme() {
+            Some(Outcome::ThemeChanged)
+        } else {
+            Some(Outcome::SettingsChanged)
+        }
+    }
+    // 1日の目標歩数を変えてセーブする
+    fn change_goal(&mut self, step_count: i32) -> Option<Outcome> {
+        self.goals.set_step_count(self.goals.step_count() + step_count);
+        self.events.emit(Event::SettingsChanged);
+        Some(Outcome::GoalChanged)
+    }
+    // 選んでいる体格を1cmか1kgずつ変えてセーブする。今日歩いた分は計算し直さない
+    fn change_profile(&mut self, amount: i32) -> Option<Outcome> {
+        match self.settings.selected {
+            SettingItem::Height => self.profile.change_height(amount),
+            SettingItem::Weight => self.profile.change_weight(amount),
+            SettingItem::Stride => self.profile.change_stride(amount),
+            _ => return None,
+        }
+        self.events.emit(Event::SettingsChanged);
+        Some(Outcome::SettingsChanged)
+    }
+    // 時計を合わせてセーブする。夜になって色が変わることもあるので、画面全体を描き直す
+    fn set_time(&mut self, now: DateTime) -> Option<Outcome> {
+        self.update_time(now);
+        self.events.emit(Event::SettingsChanged);
+        Some(Outcome::TimeSet)
+    }
+    fn press(&mut self) -> Option<Outcome> {
+        // 現在のページではないナビゲーションを指し示しているかつ、ルーティングがGameでないとき
+        if self.navigation.focus != self.router.route && self.router.route != Route::Game {
+            self.route(self.navigation.focus);
+            return Some(Outcome::Routed(self.router.route));
+        }
+
+        match self.router.route {
+            Route::Home => Some(Outcome::Touched),
+            Route::Meal if self.meal.amount > 0 => {
+                // 食べる
+                self.events.emit(Event::Ate(self.meal.amount));
+                Needs::eat(&mut self.needs, self.meal.amount);
+                Character::eat(&mut self.character, &mut self.meal, &mut self.rice_ball);
+                self.go_home();
+                self.scenes.play(&[Shot { scene: Scene::Eat, duration_ms: SCENE_DURATION_MS }]);
+                Some(Outcome::Ate)
+            }
+            Route::Play if self.bet.amount > 0 => {
+                // ルーティングをGameにアップデート
+                self.route(Route::Game);
+                Some(Outcome::Routed(Route::Game))
+            }
+            Route::Game => {
+                // 手裏剣が真ん中の的を射ているかどうかを判定する
+                let outcome = match self.target.position {
+                    TargetPosition::Center => {
+                        // BETした倍の親密度UP
+                        Character::intimate(&mut self.character, self.bet.amount * 2);
+                        Outcome::GameWon
+                    }
+                    TargetPosition::Left | TargetPosition::Right => Outcome::GameLost,
+                };
+                self.events.emit(if outcome == Outcome::GameWon { Event::GameWon } else { Event::GameLost });
+                Needs::play(&mut self.needs, self.bet.amount, outcome == Outcome::GameWon);
+                // 手裏剣を消費してBETをリセット
+                Character::play(&mut self.character, &mut self.bet, &mut self.shuriken);
+                self.go_home();
+                // 投げる様子を見せてから結果を見せる
+                let result = if outcome == Outcome::GameWon { Scene::Success } else { Scene::Fail };
+                self.scenes.play(&[
+                    Shot { scene: Scene::Throw, duration_ms: SCENE_DURATION_MS },
+                    Shot { scene: result, duration_ms: SCENE_DURATION_MS },
+                ]);
+                Some(outcome)
+            }
+            // 上下のボタンで変える設定を選ぶ
+            Route::Stats => {
+                self.settings.select_next();
+                Some(Outcome::SettingsChanged)
+            }
+            Route::Meal | Route::Play => None,
+        }
+    }
+    fn skip_scene(&mut self, event: InputEvent) -> Option<Outcome> {
+        match event {
+            InputEvent::Press => Some(scene_outcome(self.scenes.skip())),
+            _ => None,
+        }
+    }
+    // Homeに遷移する
+    fn go_home(&mut self) {
+        self.navigation.focus = Route::Home;
+        self.route(Route::Home);
+    }
+    fn route(&mut self, route: Route) {
+        Router::update(&mut self.router, route);
+        self.events.emit(Event::RouteChanged(route));
+    }
+}
+
+fn scene_outcome(transition: Transition) -> Outcome {
+    match transition {
+        Transition::Changed => Outcome::SceneChanged,
+        Transition::Finished => Outcome::SceneEnded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::FRAME_INTERVAL_MS;
+    use crate::power::{
+        PowerState,
+        DIM_AFTER_MS,
+        OFF_AFTER_MS,
+        WAKE_STEPS,
+    };
+    use crate::pedometer::{
+        Detector,
+        DetectorKind,
+        PeakDetector,
+    };
+    use crate::settings::{
+        SettingItem,
+        ThemeMode,
+        MAX_BRIGHTNESS,
+    };
+    use crate::goal::{
+        Goal,
+        Reward,
+    };
+    use accelerometer::vector::F32x3;
+
+    fn drain(game: &mut Game) -> Vec<Event> {
+        game.events.drain().collect()
+    }
+
+    // 前のサンプルから300ミリ秒ごとのサンプルをまとめて渡す
+    fn sample(game: &mut Game, zs: &[f32]) -> Option<Outcome> {
+        let start_ms = game.pedometer.last_timestamp_ms.map_or(0, |last| last + 300);
+        let samples = zs.iter().enumerate().map(|(index, z)| Sample {
+            timestamp_ms: start_ms + index as u32 * 300,
+            accel: F32x3::new(0.0, 0.0, *z),
+        });
+
+        game.sample(samples.collect::<Vec<_>>())
+    }
+
+    // 踏み込んで戻すまでのサンプルを渡して1歩あるく。戻したときに1歩と数える
+    fn step(game: &mut Game) -> Option<Outcome> {
+        sample(game, &[1.0, 2.0, 1.0])
+    }
+
+    // 歩き始めを確かめずに、1歩ずつすぐ数える
+    fn count_every_step(game: &mut Game) {
+        game.pedometer.detector = Detector::Peak(PeakDetector {
+            min_regular_steps: 1,
+            ..PeakDetector::new()
+        });
+    }
+
+    fn game_with_items(amount: i32) -> Game {
+        let mut game = Game::new();
+        game.rice_ball.amount = amount;
+        game.shuriken.amount = amount;
+        game
+    }
+
+    #[test]
+    fn test_navigate() {
+        let mut game = Game::new();
+
+        assert_eq!(game.handle(InputEvent::Right), Some(Outcome::Focused(Route::Meal)));
+        assert_eq!(game.router.route, Route::Home);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::Routed(Route::Meal)));
+        assert_eq!(game.router.route, Route::Meal);
+
+        assert_eq!(game.handle(InputEvent::Left), Some(Outcome::Focused(Route::Home)));
+        assert_eq!(game.router.route, Route::Meal);
+    }
+
+    #[test]
+    fn test_touch_on_home() {
+        let mut game = Game::new();
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::Touched));
+        assert_eq!(game.handle(InputEvent::Up), None);
+        assert_eq!(game.handle(InputEvent::Down), None);
+    }
+
+    #[test]
+    fn test_eat_session() {
+        let mut game = game_with_items(3);
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+
+        // 食事量が0のときは食べない
+        assert_eq!(game.handle(InputEvent::Press), None);
+
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Down);
+
+        assert_eq!(game.meal.amount, 2);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::Ate));
+        assert_eq!(game.rice_ball.amount, 1);
+        assert_eq!(game.meal.amount, 0);
+        assert_eq!(game.character.intimacy, 2);
+        assert_eq!(game.router.route, Route::Home);
+        assert_eq!(game.navigation.focus, Route::Home);
+    }
+
+    #[test]
+    fn test_game_won_session() {
+        let mut game = game_with_items(2);
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Up);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::Routed(Route::Game)));
+
+        // Game中はナビゲーションが動かない
+        assert_eq!(game.handle(InputEvent::Left), None);
+        assert_eq!(game.navigation.focus, Route::Play);
+
+        assert_eq!(game.tick(TARGET_INTERVAL_MS / 2), None);
+        assert_eq!(game.tick(TARGET_INTERVAL_MS / 2), Some(Outcome::TargetMoved));
+        assert_eq!(game.target.position, TargetPosition::Center);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::GameWon));
+        assert_eq!(game.character.intimacy, 4);
+        assert_eq!(game.shuriken.amount, 0);
+        assert_eq!(game.bet.amount, 0);
+        assert_eq!(game.router.route, Route::Home);
+        assert_eq!(game.navigation.focus, Route::Home);
+    }
+
+    #[test]
+    fn test_game_lost_session() {
+        let mut game = game_with_items(2);
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Press);
+
+        assert_eq!(game.target.position, TargetPosition::Left);
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::GameLost));
+        assert_eq!(drain(&mut game)[2..], [Event::GameLost, Event::RouteChanged(Route::Home)]);
+        assert_eq!(game.character.intimacy, 0);
+        assert_eq!(game.shuriken.amount, 1);
+        assert_eq!(game.router.route, Route::Home);
+    }
+
+    #[test]
+    fn test_scenes_after_game() {
+        let mut game = game_with_items(2);
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Press);
+        game.tick(TARGET_INTERVAL_MS);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::GameWon));
+        assert_eq!(game.scenes.current(), Some(Scene::Throw));
+        assert!(!game.is_home_visible());
+
+        // 演出の間はボタンを押すまでナビゲーションが動かない
+        assert_eq!(game.handle(InputEvent::Right), None);
+        assert_eq!(game.tick(FRAME_INTERVAL_MS), Some(Outcome::SceneChanged));
+        assert_eq!(game.scenes.frame(), 1);
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::SceneChanged));
+        assert_eq!(game.scenes.current(), Some(Scene::Success));
+        assert_eq!(game.tick(SCENE_DURATION_MS), Some(Outcome::SceneEnded));
+        assert!(game.is_home_visible());
+        assert_eq!(game.handle(InputEvent::Right), Some(Outcome::Focused(Route::Meal)));
+    }
+
+    #[test]
+    fn test_level_up() {
+        let mut game = game_with_items(10);
+        game.levels = Levels::new([2, 4, 6]);
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::Ate));
+        assert_eq!(
+            drain(&mut game),
+            [Event::RouteChanged(Route::Meal), Event::Ate(1), Event::RouteChanged(Route::Home)]
+        );
+        // 食べている演出を飛ばす
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::SceneEnded));
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Press);
+
+        assert_eq!(game.level(), Level::Acquaintance);
+        assert_eq!(drain(&mut game).last(), Some(&Event::LevelUp(Level::Acquaintance)));
+    }
+
+    #[test]
+    fn test_needs_session() {
+        let mut game = game_with_items(10);
+        game.needs.hunger = 0;
+
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Press);
+
+        assert_eq!(game.needs.hunger, 20);
+
+        game.handle(InputEvent::Press);
+        game.needs.happiness = 0;
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Up);
+        game.handle(InputEvent::Press);
+        game.handle(InputEvent::Press);
+
+        assert_eq!(game.needs.happiness, 5);
+    }
+
+    #[test]
+    fn test_mood_changed() {
+        let mut game = Game::new();
+        game.needs.energy = 20;
+
+        assert_eq!(game.mood(), Mood::Away);
+        assert_eq!(game.tick(59 * 1000), None);
+        assert_eq!(game.tick(1000), Some(Outcome::MoodChanged));
+        assert_eq!(game.mood(), Mood::Sleep);
+
+        // Home以外では通知しない
+        game.needs.energy = 20;
+        game.router.route = Route::Meal;
+
+        assert_eq!(game.tick(60 * 1000), None);
+    }
+
+    #[test]
+    fn test_tick_outside_game() {
+        let mut game = Game::new();
+
+        assert_eq!(game.tick(TARGET_INTERVAL_MS), None);
+        assert_eq!(game.target.position, TargetPosition::Left);
+    }
+
+    #[test]
+    fn test_update_time() {
+        let mut game = Game::new();
+        let time = game.time;
+
+        assert_eq!(game.update_time(DateTime { second: 59, ..time }), None);
+        assert_eq!(
+            game.update_time(DateTime { minute: 1, ..time }),
+            Some(Outcome::MinutePassed)
+        );
+        assert_eq!(drain(&mut game), []);
+
+        // 時が進むたびにセーブする
+        game.update_time(DateTime { hour: time.hour + 1, ..time });
+
+        assert_eq!(drain(&mut game), [Event::HourPassed]);
+    }
+
+    #[test]
+    fn test_night() {
+        let mut game = Game::new();
+        let time = game.time;
+        // 画面の色は変えずに機嫌だけを見る
+        game.settings.theme = ThemeMode::Light;
+
+        assert_eq!(game.mood(), Mood::Away);
+        assert_eq!(
+            game.update_time(DateTime { hour: 22, ..time }),
+            Some(Outcome::MoodChanged)
+        );
+        assert_eq!(game.mood(), Mood::Sleep);
+        assert_eq!(
+            game.update_time(DateTime { hour: 7, ..time }),
+            Some(Outcome::MoodChanged)
+        );
+        assert_eq!(game.mood(), Mood::Away);
+    }
+
+    #[test]
+    fn test_day_changed() {
+        let mut game = Game::new();
+        let time = game.time;
+        game.settings.theme = ThemeMode::Light;
+        game.pedometer.step_count = 1234;
+
+        assert_eq!(game.daily_step_count(), 1234);
+        assert_eq!(
+            game.update_time(DateTime { day: 2, hour: 0, ..time }),
+            Some(Outcome::DayChanged)
+        );
+        assert_eq!(game.daily_step_count(), 0);
+
+        game.pedometer.step_count = 1240;
+
+        assert_eq!(game.daily_step_count(), 6);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut game = Game::new();
+        let time = game.time;
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Right);
+        game.handle(InputEvent::Right);
+
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::Routed(Route::Stats)));
+        // Statsでは押すと変える設定を選ぶ
+        assert_eq!(game.handle(InputEvent::Press), Some(Outcome::SettingsChanged));
+        assert_eq!(game.settings.selected, SettingItem::Brightness);
+
+        count_every_step(&mut game);
+        step(&mut game);
+        game.update_time(DateTime { day: 2, ..time });
+        step(&mut game);
+
+        let days = game.pedometer.history.days.totals();
+        assert_eq!(days[days.len() - 2..], [1, 1]);
+    }
+
+    #[test]
+    fn test_switch_theme() {
+        let mut game = Game::new();
+        game.router.update(Route::Stats);
+
+        // 昼なのでAutoからLightにしても色は変わらない
+        assert_eq!(game.handle(InputEvent::Up), Some(Outcome::SettingsChanged));
+        assert_eq!(game.settings.theme, ThemeMode::Light);
+        assert_eq!(game.handle(InputEvent::Up), Some(Outcome::ThemeChanged));
+        assert_eq!(game.settings.theme, ThemeMode::Dark);
+        assert!(game.dark);
+        assert_eq!(game.handle(InputEvent::Down), Some(Outcome::ThemeChanged));
+        assert!(!game.dark);
+        assert_eq!(drain(&mut game), [Event::SettingsChanged; 3]);
+    }
+
+    #[test]
+    fn test_change_brightness() {
+        let mut game = Game::new();
+        game.router.update(Route::Stats);
+        game.navigation.focus = Route::Stats;
+        game.handle(InputEvent::Press);
+
+        assert_eq!(game.handle(InputEvent::Down), Some(Outcome::SettingsChanged));
+        assert_eq!(game.settings.brightness, MAX_BRIGHTNESS - 1);
+        assert_eq!(game.settings.theme, ThemeMode::Auto);
+        assert_eq!(drain(&mut game), [Event::SettingsChanged]);
+    }
+
+    #[test]
+    fn test_set_time() {
+        let mut game = Game::new();
+        game.router.update(Route::Stats);
+        game.settings.theme = ThemeMode::Light;
+        game.settings.selected = SettingItem::Hour;
+
+        // 日付を変えずに時を合わせる
+        assert_eq!(game.handle(InputEvent::Down), Some(Outcome::TimeSet));
+        assert_eq!(game.time.hour, 11);
+        for _ in 0..12 {
+            game.handle(InputEvent::Up);
+        }
+        assert_eq!(game.time.hour, 23);
+        assert_eq!(game.time.day, 1);
+        assert_eq!(game.mood(), Mood::Sleep);
+
+        game.settings.selected = SettingItem::Minute;
+        assert_eq!(game.handle(InputEvent::Down), Some(Outcome::TimeSet));
+        assert_eq!((game.time.hour, game.time.minute), (23, 59));
+        assert!(drain(&mut game).contains(&Event::SettingsChanged));
+    }
+
+    #[test]
+    fn test_change_goal() {
+        let mut game = Game::new();
+        game.router.update(Route::Stats);
+        game.settings.selected = SettingItem::Goal;
+
+        assert_eq!(game.handle(InputEvent::Down), Some(Outcome::GoalChanged));
+        assert_eq!(game.goals.step_count(), 9000);
+        assert_eq!(game.progress(), (0, 2700));
+        assert_eq!(drain(&mut game), [Event::SettingsChanged]);
+    }
+
+    #[test]
+    fn test_change_profile() {
+        let mut game = Game::new();
+        game.router.update(Route::Stats);
+        game.settings.selected = SettingItem::Weight;
+
+        assert_eq!(game.handle(InputEvent::Down), Some(Outcome::SettingsChanged));
+        assert_eq!(game.profile.weight_kg, 59);
+
+        game.settings.selected = SettingItem::Height;
+        game.handle(InputEvent::Up);
+        game.settings.selected = SettingItem::Stride;
+        game.handle(InputEvent::Up);
+
+        assert_eq!(game.profile, Profile::new(171, 59, Some(70)));
+        assert_eq!(drain(&mut game), [Event::SettingsChanged; 3]);
+    }
+
+    #[test]
+    fn test_switch_detector() {
+        let mut game = Game::new();
+        game.router.update(Route::Stats);
+        game.settings.selected = SettingItem::Detector;
+
+        assert_eq!(game.handle(InputEvent::Up), Some(Outcome::SettingsChanged));
+        assert_eq!(game.pedometer.detector.kind(), DetectorKind::Threshold);
+        assert_eq!(drain(&mut game), [Event::SettingsChanged]);
+    }
+
+    #[test]
+    fn test_power_timeout() {
+        let mut game = Game::new();
+
+        game.tick(DIM_AFTER_MS);
+        assert_eq!(game.power.state, PowerState::Dimmed);
+        game.tick(OFF_AFTER_MS - DIM_AFTER_MS);
+        assert_eq!(game.power.state, PowerState::Off);
+
+        // 消えているときのボタンは画面を点けるだけ
+        assert_eq!(game.handle(InputEvent::Right), None);
+        assert_eq!(game.power.state, PowerState::On);
+        assert_eq!(game.navigation.focus, Route::Home);
+        assert_eq!(game.handle(InputEvent::Right), Some(Outcome::Focused(Route::Meal)));
+    }
+
+    #[test]
+    fn test_power_wake_on_steps() {
+        let mut game = Game::new();
+        game.tick(OFF_AFTER_MS);
+
+        // 続けて歩いたら画面が点く
+        for _ in 0..WAKE_STEPS {
+            step(&mut game);
+        }
+        assert_eq!(game.power.state, PowerState::On);
+    }
+
+    #[test]
+    fn test_auto_theme() {
+        let mut game = Game::new();
+        let time = game.time;
+
+        assert!(!game.dark);
+        assert_eq!(
+            game.update_time(DateTime { hour: 22, ..time }),
+            Some(Outcome::ThemeChanged)
+        );
+        assert!(game.dark);
+
+        // 演出の途中では色を変えず、終わってから変える
+        game.scenes.play(&[Shot { scene: Scene::Eat, duration_ms: SCENE_DURATION_MS }]);
+        assert_eq!(game.update_time(DateTime { hour: 7, ..time }), Some(Outcome::MinutePassed));
+        assert!(game.dark);
+
+        game.handle(InputEvent::Press);
+        assert_eq!(game.update_time(DateTime { hour: 7, ..time }), Some(Outcome::ThemeChanged));
+        assert!(!game.dark);
+    }
+
+    #[test]
+    fn test_goal_reached() {
+        let mut game = Game::new();
+        game.goals = Goals::new(&[Goal {
+            step_count: 2,
+            reward: Reward { rice_ball: 3, shuriken: 2, intimacy: 10 },
+        }]);
+        count_every_step(&mut game);
+
+        assert_eq!(step(&mut game), Some(Outcome::Walked));
+        assert_eq!(step(&mut game), Some(Outcome::GoalReached));
+        assert_eq!(game.rice_ball.amount, 3);
+        assert_eq!(game.shuriken.amount, 2);
+        assert_eq!(
+            drain(&mut game)[1..],
+            [
+                Event::Walked(1),
+                Event::GoalReached(game.goals.goals[0]),
+                Event::LevelUp(Level::Acquaintance),
+            ]
+        );
+
+        // 次の日はまた最初の目標から
+        let time = game.time;
+        game.update_time(DateTime { day: 2, ..time });
+
+        assert_eq!(game.goals.achieved, 0);
+        assert_eq!(game.exercise, Exercise::new());
+        assert_eq!(drain(&mut game), [Event::DayChanged]);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut game = Game::new();
+        count_every_step(&mut game);
+
+        // 踏み込んだだけではまだ数えない
+        assert_eq!(sample(&mut game, &[1.0, 2.0]), None);
+        assert_eq!(sample(&mut game, &[1.0]), Some(Outcome::Walked));
+        assert_eq!(game.pedometer.step_count, 1);
+    }
+
+    #[test]
+    fn test_activity_changed() {
+        let mut game = Game::new();
+        game.classifier.window_ms = 1000;
+        let samples = (0..=10).map(|index| Sample {
+            timestamp_ms: index * 100,
+            accel: F32x3::new(0.0, 0.0, if index % 3 == 1 { 1.5 } else { 1.0 }),
+        });
+
+        assert_eq!(game.sample(samples), Some(Outcome::ActivityChanged));
+        assert_eq!(game.activity(), Activity::Running);
+        assert_eq!(game.mood(), Mood::Happy);
+    }
+
+    #[test]
+    fn test_sample_batch() {
+        let mut game = Game::new();
+        game.discovery.rice_ball_interval = 1;
+        count_every_step(&mut game);
+        let samples = (0..=40).map(|index| Sample {
+            timestamp_ms: index * 300,
+            accel: F32x3::new(0.0, 0.0, if index % 2 == 0 { 1.0 } else { 2.0 }),
+        });
+
+        // まとめて渡しても、1歩ごとにアイテムを見つける。12秒歩いたので歩いていると判定する
+        assert_eq!(game.sample(samples), Some(Outcome::ActivityChanged));
+        assert_eq!(game.pedometer.step_count, 20);
+        assert_eq!(game.rice_ball.amount, 20);
+        assert_eq!(
+            drain(&mut game).iter().filter(|event| **event == Event::ItemFound(Item::RiceBall)).count(),
+            20
+        );
+        assert_eq!(game.exercise.distance_cm, 20 * game.profile.stride_cm(0));
+        assert_eq!(game.sample(None), None);
+    }
+}
@@ -0,0 +1,168 @@
+use heapless::{consts::*, Vec};
+
+// 1日の目標歩数の初期値と、設定で変えられる範囲と刻み
+pub const DEFAULT_GOAL_STEP_COUNT: i32 = 10000;
+pub const MIN_GOAL_STEP_COUNT: i32 = 1000;
+pub const MAX_GOAL_STEP_COUNT: i32 = 30000;
+pub const GOAL_STEP_INTERVAL: i32 = 1000;
+
+// 目標を達成したときにもらえるもの
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reward {
+    pub rice_ball: i32,
+    pub shuriken: i32,
+    pub intimacy: i32,
+}
+
+// 1日の歩数の目標
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Goal {
+    pub step_count: i32,
+    pub reward: Reward,
+}
+
+// 1日に設定できる目標。歩数の少ない順に達成していく
+pub struct Goals {
+    pub goals: Vec<Goal, U4>,
+    // 今日達成した目標の数
+    pub achieved: usize,
+}
+
+impl Default for Goals {
+    fn default() -> Self {
+        Goals::daily(DEFAULT_GOAL_STEP_COUNT)
+    }
+}
+
+impl Goals {
+    // 1日の目標歩数の3割、6割、全部を歩いたらご褒美をもらう
+    pub fn daily(step_count: i32) -> Goals {
+        Goals::new(&[
+            Goal {
+                step_count: step_count * 3 / 10,
+                reward: Reward { rice_ball: 1, shuriken: 1, intimacy: 1 },
+            },
+            Goal {
+                step_count: step_count * 6 / 10,
+                reward: Reward { rice_ball: 2, shuriken: 2, intimacy: 3 },
+            },
+            Goal {
+                step_count,
+                reward: Reward { rice_ball: 3, shuriken: 3, intimacy: 5 },
+            },
+        ])
+    }
+    // 1日の目標歩数。一番多い目標の歩数にする
+    pub fn step_count(&self) -> i32 {
+        self.goals.last().map_or(0, |goal| goal.step_count)
+    }
+    // 1日の目標歩数を変える。今日達成した数はそのまま残して、同じご褒美を2回もらわないようにする
+    pub fn set_step_count(&mut self, step_count: i32) {
+        let achieved = self.achieved;
+        *self = Goals::daily(step_count.clamp(MIN_GOAL_STEP_COUNT, MAX_GOAL_STEP_COUNT));
+        self.achieved = achieved.min(self.goals.len());
+    }
+    // 入りきらない目標は捨てる
+    pub fn new(goals: &[Goal]) -> Goals {
+        let mut goals: Vec<Goal, U4> = goals.iter().copied().take(4).collect();
+        goals.sort_unstable_by_key(|goal| goal.step_count);
+
+        Goals { goals, achieved: 0 }
+    }
+    // 次に目指す目標
+    pub fn next(&self) -> Option<&Goal> {
+        self.goals.get(self.achieved)
+    }
+    // 今日の歩数で新しく達成した目標があれば1つずつ返す
+    pub fn check(&mut self, daily_step_count: i32) -> Option<Goal> {
+        let goal = *self.next()?;
+
+        if daily_step_count < goal.step_count {
+            return None;
+        }
+
+        self.achieved += 1;
+        Some(goal)
+    }
+    // 日付が変わったら最初の目標からやり直す
+    pub fn reset(&mut self) {
+        self.achieved = 0;
+    }
+    // 次の目標までの進み具合を(今の歩数, 目標の歩数)で返す。全部達成したら満タンにする
+    pub fn progress(&self, daily_step_count: i32) -> (i32, i32) {
+        match self.next() {
+            Some(goal) => (daily_step_count.min(goal.step_count), goal.step_count),
+            None if self.goals.is_empty() => (0, 1),
+            None => (1, 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal(step_count: i32, intimacy: i32) -> Goal {
+        Goal {
+            step_count,
+            reward: Reward { rice_ball: 1, shuriken: 1, intimacy },
+        }
+    }
+
+    #[test]
+    fn test_new_sorts_goals() {
+        let goals = Goals::new(&[goal(200, 2), goal(100, 1)]);
+
+        assert_eq!(goals.next(), Some(&goal(100, 1)));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut goals = Goals::new(&[goal(100, 1), goal(200, 2)]);
+
+        assert_eq!(goals.check(99), None);
+        assert_eq!(goals.check(250), Some(goal(100, 1)));
+        assert_eq!(goals.check(250), Some(goal(200, 2)));
+        assert_eq!(goals.check(250), None);
+
+        goals.reset();
+
+        assert_eq!(goals.check(100), Some(goal(100, 1)));
+    }
+
+    #[test]
+    fn test_daily() {
+        let goals = Goals::default();
+
+        assert_eq!(goals.goals.iter().map(|goal| goal.step_count).collect::<Vec<_, U4>>(), [3000, 6000, 10000]);
+        assert_eq!(goals.step_count(), DEFAULT_GOAL_STEP_COUNT);
+    }
+
+    #[test]
+    fn test_set_step_count() {
+        let mut goals = Goals::default();
+        goals.check(3000);
+
+        goals.set_step_count(5000);
+
+        assert_eq!(goals.step_count(), 5000);
+        assert_eq!(goals.achieved, 1);
+        assert_eq!(goals.check(3000), Some(Goals::daily(5000).goals[1]));
+
+        goals.set_step_count(0);
+
+        assert_eq!(goals.step_count(), MIN_GOAL_STEP_COUNT);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut goals = Goals::new(&[goal(100, 1)]);
+
+        assert_eq!(goals.progress(40), (40, 100));
+
+        goals.check(120);
+
+        assert_eq!(goals.progress(120), (1, 1));
+        assert_eq!(Goals::new(&[]).progress(120), (0, 1));
+    }
+}
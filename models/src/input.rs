@@ -0,0 +1,14 @@
+// Wio Terminalの5方向スイッチを抽象化した入力
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    // switch_u
+    Up,
+    // switch_x
+    Down,
+    // switch_b
+    Left,
+    // switch_y
+    Right,
+    // switch_z
+    Press,
+}
@@ -0,0 +1,287 @@
+use crate::{
+    crc::crc32,
+    flash::Flash,
+    save::RECORD_SIZE,
+    storage::Storage,
+};
+
+// 通し番号(4) + レコード + CRC(4) を、SAMD51のクアッドワード(16byte)の倍数に切り上げた大きさ
+pub const ENTRY_SIZE: usize = (SEQUENCE_SIZE + RECORD_SIZE + CRC_SIZE + 15) / 16 * 16;
+
+const SEQUENCE_SIZE: usize = 4;
+const CRC_POSITION: usize = SEQUENCE_SIZE + RECORD_SIZE;
+const CRC_SIZE: usize = 4;
+
+enum Slot {
+    // 消去済みで書き込める
+    Erased,
+    // 正しく書き込まれたエントリ
+    Valid(u32),
+    // 書き込み途中で電源が切れたなどで壊れている
+    Invalid,
+}
+
+// フラッシュの複数ページにレコードを追記していくジャーナル。
+// 各エントリはレコード全体を持つので、一番新しいエントリだけを読めばよい。
+// ページが埋まったら次のページを消去して続きを書くので、消去回数が全ページに分散する。
+// 書き込み途中で電源が切れても、CRCの合わないエントリは無視されて一つ前のレコードが読まれる。
+pub struct Journal<F: Flash> {
+    pub flash: F,
+    // 一番新しいエントリの位置
+    latest: Option<(usize, usize)>,
+    // 次に書き込む位置
+    page: usize,
+    slot: usize,
+    sequence: u32,
+}
+
+impl<F: Flash> Journal<F> {
+    // フラッシュを走査して、一番新しいエントリと次に書き込む位置を探す
+    pub fn mount(flash: F) -> Result<Journal<F>, F::Error> {
+        let mut journal = Journal {
+            flash,
+            latest: None,
+            page: 0,
+            slot: 0,
+            sequence: 0,
+        };
+
+        for page in 0..journal.flash.page_count() {
+            for slot in 0..journal.slots_per_page() {
+                if let Slot::Valid(sequence) = journal.read_slot(page, slot)? {
+                    let is_newer = match journal.latest {
+                        // 通し番号が一周しても比較できるように差で比べる
+                        Some(_) => (sequence.wrapping_sub(journal.sequence) as i32) > 0,
+                        None => true,
+                    };
+                    if is_newer {
+                        journal.latest = Some((page, slot));
+                        journal.sequence = sequence;
+                    }
+                }
+            }
+        }
+
+        if let Some((page, slot)) = journal.latest {
+            journal.page = page;
+            journal.slot = slot + 1;
+        }
+
+        Ok(journal)
+    }
+    fn slots_per_page(&self) -> usize {
+        self.flash.page_size() / ENTRY_SIZE
+    }
+    fn address(&self, page: usize, slot: usize) -> usize {
+        page * self.flash.page_size() + slot * ENTRY_SIZE
+    }
+    fn read_slot(&mut self, page: usize, slot: usize) -> Result<Slot, F::Error> {
+        let mut entry = [0; ENTRY_SIZE];
+        self.flash.read(self.address(page, slot), &mut entry)?;
+
+        if entry.iter().all(|byte| *byte == 0xFF) {
+            return Ok(Slot::Erased);
+        }
+
+        let mut crc = [0; CRC_SIZE];
+        crc.copy_from_slice(&entry[CRC_POSITION..CRC_POSITION + CRC_SIZE]);
+        if u32::from_le_bytes(crc) != crc32(&entry[..CRC_POSITION]) {
+            return Ok(Slot::Invalid);
+        }
+
+        let mut sequence = [0; SEQUENCE_SIZE];
+        sequence.copy_from_slice(&entry[..SEQUENCE_SIZE]);
+        Ok(Slot::Valid(u32::from_le_bytes(sequence)))
+    }
+    // 消去済みの位置まで進める。ページの終わりに来たら次のページを消去して使う
+    fn seek_erased_slot(&mut self) -> Result<(), F::Error> {
+        loop {
+            if self.slot >= self.slots_per_page() {
+                self.page = (self.page + 1) % self.flash.page_count();
+                self.slot = 0;
+                self.flash.erase(self.page)?;
+            }
+
+            if let Slot::Erased = self.read_slot(self.page, self.slot)? {
+                return Ok(());
+            }
+
+            self.slot += 1;
+        }
+    }
+}
+
+impl<F: Flash> Storage for Journal<F> {
+    type Error = F::Error;
+
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), Self::Error> {
+        match self.latest {
+            Some((page, slot)) => {
+                let address = self.address(page, slot) + SEQUENCE_SIZE;
+                self.flash.read(address, record)
+            }
+            None => {
+                *record = [0xFF; RECORD_SIZE];
+                Ok(())
+            }
+        }
+    }
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), Self::Error> {
+        self.seek_erased_slot()?;
+
+        let sequence = self.sequence.wrapping_add(1);
+        let mut entry = [0xFF; ENTRY_SIZE];
+        entry[..SEQUENCE_SIZE].copy_from_slice(&sequence.to_le_bytes());
+        entry[SEQUENCE_SIZE..CRC_POSITION].copy_from_slice(record);
+        let crc = crc32(&entry[..CRC_POSITION]);
+        entry[CRC_POSITION..CRC_POSITION + CRC_SIZE].copy_from_slice(&crc.to_le_bytes());
+
+        let (page, slot) = (self.page, self.slot);
+        // 書き込みに失敗しても、次は壊れたエントリの後ろに書く
+        self.slot += 1;
+        self.flash.write(self.address(page, slot), &entry)?;
+
+        self.latest = Some((page, slot));
+        self.sequence = sequence;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        flash::{
+            RamFlash,
+            RamFlashError,
+        },
+        save::{
+            SaveData,
+            SaveError,
+        },
+    };
+
+    // 1ページに3エントリ入る4ページのフラッシュ
+    type TestFlash = RamFlash<{ ENTRY_SIZE * 3 }, 4>;
+
+    fn save_data(step_count: i32) -> SaveData {
+        SaveData {
+            step_count,
+            ..SaveData::default()
+        }
+    }
+
+    fn load(journal: &mut Journal<TestFlash>) -> Result<SaveData, SaveError<RamFlashError>> {
+        SaveData::load(journal)
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+
+        assert_eq!(load(&mut journal), Err(SaveError::Empty));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+
+        for step_count in 0..10 {
+            save_data(step_count).save(&mut journal).unwrap();
+
+            assert_eq!(load(&mut journal), Ok(save_data(step_count)));
+        }
+    }
+
+    #[test]
+    fn test_mount_finds_latest() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+
+        for step_count in 0..7 {
+            save_data(step_count).save(&mut journal).unwrap();
+        }
+
+        let mut journal = Journal::mount(journal.flash).unwrap();
+
+        assert_eq!(load(&mut journal), Ok(save_data(6)));
+
+        save_data(7).save(&mut journal).unwrap();
+        let mut journal = Journal::mount(journal.flash).unwrap();
+
+        assert_eq!(load(&mut journal), Ok(save_data(7)));
+    }
+
+    #[test]
+    fn test_wear_levelling() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+
+        for step_count in 0..120 {
+            save_data(step_count).save(&mut journal).unwrap();
+        }
+
+        // 40ページ分を書き込み、最初のページ以外の39回の消去が4ページに均等に分散する
+        assert_eq!(journal.flash.erase_counts, [9, 10, 10, 10]);
+        assert_eq!(load(&mut journal), Ok(save_data(119)));
+    }
+
+    #[test]
+    fn test_torn_write() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+
+        save_data(1).save(&mut journal).unwrap();
+        save_data(2).save(&mut journal).unwrap();
+
+        // 書き込み途中で電源が切れる
+        journal.flash.tear_after = Some(ENTRY_SIZE / 2);
+
+        assert_eq!(
+            save_data(3).save(&mut journal),
+            Err(SaveError::Storage(RamFlashError::PowerLoss))
+        );
+
+        journal.flash.tear_after = None;
+        let mut journal = Journal::mount(journal.flash).unwrap();
+
+        assert_eq!(load(&mut journal), Ok(save_data(2)));
+
+        // 壊れたエントリを飛ばして次のページに書き込める
+        save_data(4).save(&mut journal).unwrap();
+        let mut journal = Journal::mount(journal.flash).unwrap();
+
+        assert_eq!(load(&mut journal), Ok(save_data(4)));
+        assert_eq!(journal.flash.erase_counts, [0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_torn_write_after_erase() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+
+        for step_count in 0..3 {
+            save_data(step_count).save(&mut journal).unwrap();
+        }
+
+        // 次のページを消去した直後に電源が切れても、前のページの最新のレコードが残る
+        journal.flash.tear_after = Some(0);
+
+        assert!(save_data(3).save(&mut journal).is_err());
+
+        journal.flash.tear_after = None;
+        let mut journal = Journal::mount(journal.flash).unwrap();
+
+        assert_eq!(load(&mut journal), Ok(save_data(2)));
+    }
+
+    #[test]
+    fn test_sequence_wraps() {
+        let mut journal = Journal::mount(TestFlash::new()).unwrap();
+        journal.sequence = u32::MAX - 1;
+
+        for step_count in 0..4 {
+            save_data(step_count).save(&mut journal).unwrap();
+        }
+
+        let mut journal = Journal::mount(journal.flash).unwrap();
+
+        assert_eq!(load(&mut journal), Ok(save_data(3)));
+    }
+}
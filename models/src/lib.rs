@@ -1,11 +1,28 @@
 #![cfg_attr(not(test), no_std)]
 
+pub mod activity;
 pub mod bet;
 pub mod character;
+pub mod clock;
+pub mod crc;
+pub mod event;
+pub mod flash;
+pub mod game;
+pub mod goal;
+pub mod input;
+pub mod journal;
 pub mod meal;
 pub mod navigation;
+pub mod needs;
 pub mod pedometer;
+pub mod power;
+pub mod profile;
 pub mod rice_ball;
 pub mod router;
+pub mod save;
+pub mod scene;
+pub mod settings;
 pub mod shuriken;
+pub mod storage;
 pub mod target;
+pub mod trace;
@@ -25,6 +25,10 @@ impl Navigation {
             },
             Route::Play => match direction {
                 Direction::Left => self.focus = Route::Meal,
+                Direction::Right => self.focus = Route::Stats,
+            },
+            Route::Stats => match direction {
+                Direction::Left => self.focus = Route::Play,
                 Direction::Right => {}
             },
             Route::Game => {},
@@ -87,6 +91,24 @@ mod tests {
 
         Navigation::update(&mut navigation, Direction::Right);
 
+        assert_eq!(navigation.focus, Route::Stats);
+    }
+
+    #[test]
+    fn test_update_stats_to_left() {
+        let mut navigation = Navigation { focus: Route::Stats };
+
+        Navigation::update(&mut navigation, Direction::Left);
+
         assert_eq!(navigation.focus, Route::Play);
     }
+
+    #[test]
+    fn test_update_stats_to_right() {
+        let mut navigation = Navigation { focus: Route::Stats };
+
+        Navigation::update(&mut navigation, Direction::Right);
+
+        assert_eq!(navigation.focus, Route::Stats);
+    }
 }
@@ -0,0 +1,196 @@
+use crate::character::Level;
+
+pub const MAX_NEED: i32 = 100;
+// これより低くなると機嫌に表れる
+const LOW_NEED: i32 = 20;
+// 欲求が減る間隔(ミリ秒)
+const DECAY_INTERVAL_MS: u32 = 60 * 1000;
+// 1回あたりに減る量
+const HUNGER_DECAY: i32 = 2;
+const HAPPINESS_DECAY: i32 = 1;
+const ENERGY_DECAY: i32 = 1;
+// おにぎり1個で回復する量
+const HUNGER_PER_RICE_BALL: i32 = 10;
+const ENERGY_PER_RICE_BALL: i32 = 5;
+// 手裏剣1個で回復、消費する量
+const HAPPINESS_PER_WON_SHURIKEN: i32 = 10;
+const HAPPINESS_PER_LOST_SHURIKEN: i32 = 5;
+const ENERGY_PER_SHURIKEN: i32 = 2;
+
+// キャラクターの機嫌
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mood {
+    Angry,
+    Away,
+    Happy,
+    Shy,
+    Sleep,
+}
+
+impl Mood {
+    // 欲求が満たされているときはLevelに応じた機嫌になる
+    pub fn from_level(level: Level) -> Mood {
+        match level {
+            Level::Stranger => Mood::Away,
+            Level::Acquaintance => Mood::Shy,
+            Level::Friend => Mood::Sleep,
+            Level::BestFriend => Mood::Happy,
+        }
+    }
+}
+
+// 時間とともに減っていく欲求。どれも0から100で、高いほど満たされている
+pub struct Needs {
+    // お腹の満たされ具合
+    pub hunger: i32,
+    pub happiness: i32,
+    pub energy: i32,
+    pub elapsed_ms: u32,
+}
+
+impl Default for Needs {
+    fn default() -> Self {
+        Needs::new()
+    }
+}
+
+impl Needs {
+    pub fn new() -> Needs {
+        Needs {
+            hunger: MAX_NEED,
+            happiness: MAX_NEED,
+            energy: MAX_NEED,
+            elapsed_ms: 0,
+        }
+    }
+    // 経過時間を進めて、一定時間ごとに欲求を減らす
+    pub fn tick(&mut self, dt_ms: u32) {
+        self.elapsed_ms += dt_ms;
+
+        while self.elapsed_ms >= DECAY_INTERVAL_MS {
+            self.elapsed_ms -= DECAY_INTERVAL_MS;
+            self.hunger = clamp(self.hunger - HUNGER_DECAY);
+            self.happiness = clamp(self.happiness - HAPPINESS_DECAY);
+            self.energy = clamp(self.energy - ENERGY_DECAY);
+        }
+    }
+    // おにぎりを食べてお腹と元気が回復する
+    pub fn eat(&mut self, amount: i32) {
+        self.hunger = clamp(self.hunger + amount * HUNGER_PER_RICE_BALL);
+        self.energy = clamp(self.energy + amount * ENERGY_PER_RICE_BALL);
+    }
+    // 手裏剣で遊んで楽しくなるが、元気を使う
+    pub fn play(&mut self, bet: i32, won: bool) {
+        let happiness = if won {
+            HAPPINESS_PER_WON_SHURIKEN
+        } else {
+            HAPPINESS_PER_LOST_SHURIKEN
+        };
+
+        self.happiness = clamp(self.happiness + bet * happiness);
+        self.energy = clamp(self.energy - bet * ENERGY_PER_SHURIKEN);
+    }
+    // 足りない欲求があればそれが機嫌に表れる
+    pub fn mood(&self, level: Level) -> Mood {
+        if self.energy < LOW_NEED {
+            Mood::Sleep
+        } else if self.hunger < LOW_NEED {
+            Mood::Angry
+        } else if self.happiness < LOW_NEED {
+            Mood::Away
+        } else {
+            Mood::from_level(level)
+        }
+    }
+}
+
+fn clamp(value: i32) -> i32 {
+    value.clamp(0, MAX_NEED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick() {
+        let mut needs = Needs::new();
+
+        needs.tick(DECAY_INTERVAL_MS - 1);
+
+        assert_eq!(needs.hunger, MAX_NEED);
+
+        needs.tick(1);
+
+        assert_eq!(needs.hunger, MAX_NEED - HUNGER_DECAY);
+        assert_eq!(needs.happiness, MAX_NEED - HAPPINESS_DECAY);
+        assert_eq!(needs.energy, MAX_NEED - ENERGY_DECAY);
+        assert_eq!(needs.elapsed_ms, 0);
+    }
+
+    #[test]
+    fn test_tick_is_deterministic() {
+        let mut needs1 = Needs::new();
+        let mut needs2 = Needs::new();
+
+        // 同じ時間なら刻み方によらず同じ結果になる
+        for _ in 0..600 {
+            needs1.tick(100);
+        }
+        needs2.tick(60 * 1000);
+
+        assert_eq!(needs1.hunger, needs2.hunger);
+        assert_eq!(needs1.elapsed_ms, needs2.elapsed_ms);
+
+        needs1.tick(DECAY_INTERVAL_MS * 1000);
+
+        assert_eq!(needs1.hunger, 0);
+        assert_eq!(needs1.happiness, 0);
+        assert_eq!(needs1.energy, 0);
+    }
+
+    #[test]
+    fn test_eat() {
+        let mut needs = Needs { hunger: 10, happiness: 50, energy: 98, elapsed_ms: 0 };
+
+        needs.eat(2);
+
+        assert_eq!(needs.hunger, 30);
+        assert_eq!(needs.energy, MAX_NEED);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut needs = Needs { hunger: 50, happiness: 10, energy: 50, elapsed_ms: 0 };
+
+        needs.play(2, true);
+
+        assert_eq!(needs.happiness, 30);
+        assert_eq!(needs.energy, 46);
+
+        needs.play(2, false);
+
+        assert_eq!(needs.happiness, 40);
+        assert_eq!(needs.energy, 42);
+    }
+
+    #[test]
+    fn test_mood() {
+        let needs = Needs::new();
+
+        assert_eq!(needs.mood(Level::Stranger), Mood::Away);
+        assert_eq!(needs.mood(Level::BestFriend), Mood::Happy);
+
+        let needs = Needs { hunger: 0, happiness: 0, energy: 0, elapsed_ms: 0 };
+
+        assert_eq!(needs.mood(Level::BestFriend), Mood::Sleep);
+
+        let needs = Needs { hunger: 0, happiness: 0, energy: 50, elapsed_ms: 0 };
+
+        assert_eq!(needs.mood(Level::BestFriend), Mood::Angry);
+
+        let needs = Needs { hunger: 50, happiness: 0, energy: 50, elapsed_ms: 0 };
+
+        assert_eq!(needs.mood(Level::BestFriend), Mood::Away);
+    }
+}
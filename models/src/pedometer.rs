@@ -2,46 +2,132 @@
 use accelerometer::vector::F32x3;
 use micromath::F32Ext;
 
-pub struct Pedometer {
+use crate::clock::DateTime;
+
+// 残しておく日ごと、時間ごとの歩数の数
+pub const DAYS_OF_HISTORY: usize = 7;
+pub const HOURS_OF_HISTORY: usize = 24;
+const SECONDS_PER_HOUR: u32 = 60 * 60;
+const SECONDS_PER_DAY: u32 = 24 * SECONDS_PER_HOUR;
+
+// 一定期間ごとの歩数を、古いものから上書きしていくリングバッファ
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct History<const N: usize> {
+    pub totals: [i32; N],
+    // 今の期間の位置
+    pub current: usize,
+}
+
+impl<const N: usize> Default for History<N> {
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+impl<const N: usize> History<N> {
+    pub fn new() -> History<N> {
+        History {
+            totals: [0; N],
+            current: 0,
+        }
+    }
+    // 古い順に並んだ歩数から作る。最後が今の期間になる
+    pub fn from_totals(totals: [i32; N]) -> History<N> {
+        History {
+            totals,
+            current: N - 1,
+        }
+    }
+    pub fn add(&mut self, steps: i32) {
+        self.totals[self.current] += steps;
+    }
+    // 期間を進めて、新しい期間を0歩から数える
+    pub fn advance(&mut self, periods: u32) {
+        for _ in 0..periods.min(N as u32) {
+            self.current = (self.current + 1) % N;
+            self.totals[self.current] = 0;
+        }
+    }
+    // 古い順に並べた歩数。最後が今の期間になる
+    pub fn totals(&self) -> [i32; N] {
+        let mut totals = [0; N];
+        for (index, total) in totals.iter_mut().enumerate() {
+            *total = self.totals[(self.current + 1 + index) % N];
+        }
+        totals
+    }
+}
+
+// 日ごとと時間ごとの歩数の履歴
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StepHistory {
+    pub days: History<DAYS_OF_HISTORY>,
+    pub hours: History<HOURS_OF_HISTORY>,
+}
+
+impl StepHistory {
+    pub fn new() -> StepHistory {
+        StepHistory {
+            days: History::new(),
+            hours: History::new(),
+        }
+    }
+    pub fn add(&mut self, steps: i32) {
+        self.days.add(steps);
+        self.hours.add(steps);
+    }
+    // 時計が進んだ分だけ日と時間を進める。時計が戻ったときは何もしない
+    pub fn advance(&mut self, last_time: &DateTime, now: &DateTime) {
+        let (last, now) = (last_time.seconds(), now.seconds());
+        if now <= last {
+            return;
+        }
+
+        self.days.advance(now / SECONDS_PER_DAY - last / SECONDS_PER_DAY);
+        self.hours.advance(now / SECONDS_PER_HOUR - last / SECONDS_PER_HOUR);
+    }
+}
+
+// 加速度のサンプルから1歩を検出するアルゴリズム
+pub trait StepDetector {
+    // dt_msは前のサンプルからの経過時間。検出した歩数を返す。
+    // 歩き始めを確かめるまで溜めておいた歩数を、まとめて返すことがある
+    fn detect(&mut self, normalized_accel: F32x3, dt_ms: u32) -> i32;
+}
+
+// XYZ軸の合成値
+fn get_composite_accel(normalized_accel: F32x3) -> f32 {
+    let F32x3 { x, y, z } = normalized_accel;
+    (x.powf(2.0) + y.powf(2.0) + z.powf(2.0)).sqrt()
+}
+
+// 合成値の平均を閾値にして、閾値を上に超えたときを1歩と数える
+pub struct ThresholdDetector {
     pub sample_count: i32,
     pub total_composite_accel: f32,
     pub threshold: f32,
     pub hysteresis: f32,
-    pub step_count: i32,
     pub state: bool,
     pub last_state: bool,
 }
 
-impl Default for Pedometer {
+impl Default for ThresholdDetector {
     fn default() -> Self {
-        Pedometer::new()
+        ThresholdDetector::new()
     }
 }
 
-impl Pedometer {
-    pub fn new() -> Pedometer {
-        Pedometer {
+impl ThresholdDetector {
+    pub fn new() -> ThresholdDetector {
+        ThresholdDetector {
             sample_count: 0,
             total_composite_accel: 0.0,
             threshold: 1.5,
             hysteresis: 0.15,
-            step_count: 0,
             state: false,
             last_state: false,
         }
     }
-    pub fn update(&mut self, normalized_accel: F32x3) {
-        let composite_accel = Self::get_composite_accel(normalized_accel);
-
-        Self::set_threshold(self, composite_accel);
-        Self::set_state(self, composite_accel);
-        Self::set_step_count(self);
-    }
-    // XYZ軸の合成値
-    fn get_composite_accel(normalized_accel: F32x3) -> f32 {
-        let F32x3 { x, y, z } = normalized_accel;
-        (x.powf(2.0) + y.powf(2.0) + z.powf(2.0)).sqrt()
-    }
     // XYZ軸の合成値を、50サンプルごとに平均したものを閾値として設定する。
     // 閾値近辺の値を誤検出しないようにヒステリシスも設定する。
     fn set_threshold(&mut self, composite_accel: f32) {
@@ -66,13 +152,263 @@ impl Pedometer {
             self.state = false
         }
     }
-    // 状態をもとに歩数をカウントする
-    fn set_step_count(&mut self) {
-        if !self.last_state && self.state {
-            self.step_count += 1;
-            self.last_state = self.state;
-        } else if self.last_state && !self.state {
-            self.last_state = self.state;
+    // 状態が上がったときを1歩とする
+    fn count_step(&mut self) -> bool {
+        let is_step = !self.last_state && self.state;
+        self.last_state = self.state;
+        is_step
+    }
+}
+
+impl StepDetector for ThresholdDetector {
+    fn detect(&mut self, normalized_accel: F32x3, _dt_ms: u32) -> i32 {
+        let composite_accel = get_composite_accel(normalized_accel);
+
+        self.set_threshold(composite_accel);
+        self.set_state(composite_accel);
+        self.count_step() as i32
+    }
+}
+
+// ローパスフィルタで振動を取り除いた合成値の山を1歩と数える。
+// 山の高さはゆっくり追従する基準値から測るので、ゆっくり歩いて揺れが小さくても数えられる。
+// 前の1歩から一定時間たっていない山は乗り物の揺れなどとして数えない。
+// 乗り物の段差のようにときどき来る揺れを数えないように、同じくらいの間隔の山が続いて
+// 歩き始めたと分かるまでは数えずに溜めておき、分かったところでまとめて数える。
+pub struct PeakDetector {
+    // ローパスフィルタの時定数(ミリ秒)。大きいほど強く平滑化する
+    pub filter_time_constant_ms: u32,
+    // 基準値が追従する時定数(ミリ秒)
+    pub baseline_time_constant_ms: u32,
+    // 1歩とみなす山の最小の高さ(G)
+    pub min_peak: f32,
+    // 1歩とみなす最小の間隔(ミリ秒)
+    pub min_interval_ms: u32,
+    // これより間隔が空いたら歩くのをやめたとみなす(ミリ秒)
+    pub max_interval_ms: u32,
+    // 歩き始めたとみなすのに必要な、間隔の揃った山の数
+    pub min_regular_steps: i32,
+    // 前の間隔に対してこの倍率の範囲に収まっていれば、間隔が揃っているとみなす
+    pub regularity: f32,
+    pub filtered: f32,
+    pub baseline: f32,
+    pub rising: bool,
+    pub since_last_step_ms: u32,
+    pub last_interval_ms: u32,
+    // 歩き始めたと分かるまで溜めている歩数
+    pub pending_steps: i32,
+    pub walking: bool,
+}
+
+impl Default for PeakDetector {
+    fn default() -> Self {
+        PeakDetector::new()
+    }
+}
+
+impl PeakDetector {
+    pub fn new() -> PeakDetector {
+        PeakDetector {
+            filter_time_constant_ms: 50,
+            baseline_time_constant_ms: 2000,
+            min_peak: 0.1,
+            min_interval_ms: 250,
+            max_interval_ms: 2000,
+            min_regular_steps: 5,
+            regularity: 1.5,
+            // 静止しているときの1Gから始める
+            filtered: 1.0,
+            baseline: 1.0,
+            rising: false,
+            // まだ1歩も数えていない
+            since_last_step_ms: u32::MAX,
+            last_interval_ms: 0,
+            pending_steps: 0,
+            walking: false,
+        }
+    }
+    // 前の間隔と比べて、間隔が揃っているか
+    fn is_regular(&self, interval_ms: u32) -> bool {
+        let (interval, last_interval) = (interval_ms as f32, self.last_interval_ms as f32);
+        interval * self.regularity >= last_interval && interval <= last_interval * self.regularity
+    }
+}
+
+impl StepDetector for PeakDetector {
+    fn detect(&mut self, normalized_accel: F32x3, dt_ms: u32) -> i32 {
+        let composite_accel = get_composite_accel(normalized_accel);
+        let previous = self.filtered;
+
+        // サンプリング間隔が変わっても同じ特性になるように、時定数から係数を求める
+        let filter_factor = dt_ms as f32 / (self.filter_time_constant_ms + dt_ms) as f32;
+        let baseline_factor = dt_ms as f32 / (self.baseline_time_constant_ms + dt_ms) as f32;
+
+        self.filtered += filter_factor * (composite_accel - self.filtered);
+        self.baseline += baseline_factor * (self.filtered - self.baseline);
+        self.since_last_step_ms = self.since_last_step_ms.saturating_add(dt_ms);
+
+        // 間隔が空いたら、次はまた歩き始めを確かめてから数える
+        if self.since_last_step_ms > self.max_interval_ms {
+            self.walking = false;
+            self.pending_steps = 0;
+        }
+
+        if self.filtered > previous {
+            self.rising = true;
+            return 0;
+        }
+        if !self.rising {
+            return 0;
+        }
+
+        // 上りから下りに変わったので、一つ前のサンプルが山になる
+        self.rising = false;
+        if previous - self.baseline < self.min_peak || self.since_last_step_ms < self.min_interval_ms {
+            return 0;
+        }
+
+        let interval_ms = self.since_last_step_ms;
+        self.since_last_step_ms = 0;
+        if self.walking {
+            return 1;
+        }
+
+        // 間隔が揃っていなければ、この山から数え直す
+        if self.pending_steps >= 2 && !self.is_regular(interval_ms) {
+            self.pending_steps = 0;
+        }
+        self.pending_steps += 1;
+        self.last_interval_ms = interval_ms;
+        if self.pending_steps < self.min_regular_steps {
+            return 0;
+        }
+
+        self.walking = true;
+        core::mem::replace(&mut self.pending_steps, 0)
+    }
+}
+
+// 設定で選べる歩数の検出アルゴリズムの種類
+// 閾値方式は50サンプルごとに閾値を変えるので、50Hzでは揺れの小さい歩きを取りこぼす。
+// 初期値はサンプリング間隔によらず数えられる山の検出にする
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DetectorKind {
+    Threshold,
+    #[default]
+    Peak,
+}
+
+impl DetectorKind {
+    // セーブデータに書く番号
+    pub fn to_i32(self) -> i32 {
+        match self {
+            DetectorKind::Threshold => 0,
+            DetectorKind::Peak => 1,
+        }
+    }
+    // 知らない番号なら初期値にする
+    pub fn from_i32(value: i32) -> DetectorKind {
+        match value {
+            0 => DetectorKind::Threshold,
+            1 => DetectorKind::Peak,
+            _ => DetectorKind::default(),
+        }
+    }
+}
+
+// 設定で選べる歩数の検出アルゴリズム
+pub enum Detector {
+    Threshold(ThresholdDetector),
+    Peak(PeakDetector),
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Detector::new(DetectorKind::default())
+    }
+}
+
+impl Detector {
+    pub fn new(kind: DetectorKind) -> Detector {
+        match kind {
+            DetectorKind::Threshold => Detector::Threshold(ThresholdDetector::new()),
+            DetectorKind::Peak => Detector::Peak(PeakDetector::new()),
+        }
+    }
+    pub fn kind(&self) -> DetectorKind {
+        match self {
+            Detector::Threshold(_) => DetectorKind::Threshold,
+            Detector::Peak(_) => DetectorKind::Peak,
+        }
+    }
+}
+
+impl StepDetector for Detector {
+    fn detect(&mut self, normalized_accel: F32x3, dt_ms: u32) -> i32 {
+        match self {
+            Detector::Threshold(detector) => detector.detect(normalized_accel, dt_ms),
+            Detector::Peak(detector) => detector.detect(normalized_accel, dt_ms),
+        }
+    }
+}
+
+// 時刻つきの加速度のサンプル。時刻はサンプリングを始めてからの経過ミリ秒で、
+// 一周しても差が取れるようにラップアラウンドを許す
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub timestamp_ms: u32,
+    pub accel: F32x3,
+}
+
+impl Sample {
+    // 前のサンプルからの経過時間。最初のサンプルは0にする
+    pub fn elapsed_ms(&self, last_timestamp_ms: Option<u32>) -> u32 {
+        match last_timestamp_ms {
+            Some(last) => self.timestamp_ms.wrapping_sub(last),
+            None => 0,
+        }
+    }
+}
+
+pub struct Pedometer {
+    pub detector: Detector,
+    pub step_count: i32,
+    pub history: StepHistory,
+    // 最後に受け取ったサンプルの時刻
+    pub last_timestamp_ms: Option<u32>,
+}
+
+impl Default for Pedometer {
+    fn default() -> Self {
+        Pedometer::new()
+    }
+}
+
+impl Pedometer {
+    pub fn new() -> Pedometer {
+        Pedometer::with_detector(Detector::default())
+    }
+    pub fn with_detector(detector: Detector) -> Pedometer {
+        Pedometer {
+            detector,
+            step_count: 0,
+            history: StepHistory::new(),
+            last_timestamp_ms: None,
+        }
+    }
+    // サンプルの時刻の差を経過時間にして数える。
+    // 描画などでメインループが止まっていても、溜まったサンプルを後から正しく数えられる
+    pub fn push(&mut self, sample: Sample) {
+        let dt_ms = sample.elapsed_ms(self.last_timestamp_ms);
+        self.last_timestamp_ms = Some(sample.timestamp_ms);
+
+        self.update(sample.accel, dt_ms);
+    }
+    pub fn update(&mut self, normalized_accel: F32x3, dt_ms: u32) {
+        let steps = self.detector.detect(normalized_accel, dt_ms);
+        if steps > 0 {
+            self.step_count += steps;
+            self.history.add(steps);
         }
     }
 }
@@ -82,93 +418,238 @@ mod tests {
     use super::*;
     use accelerometer::vector::F32x3;
 
-    // TODO: 平方根のテストする方法探す
-    // #[test]
-    // fn test_get_composite_accel() {
-    //     let normalized_accel = F32x3::new(1.0, 1.0, 1.0);
+    fn assert_approx_eq(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_get_composite_accel() {
+        let normalized_accel = F32x3::new(1.0, 1.0, 1.0);
 
-    //     let composite_accel = Pedometer::get_composite_accel(normalized_accel);
+        let composite_accel = get_composite_accel(normalized_accel);
 
-    //     assert_eq!(composite_accel, 1.73205080756887729352);
-    // }
+        assert_approx_eq(composite_accel, 1.732_050_8);
+    }
 
     #[test]
     fn test_set_threshold() {
-        // TODO: f32のasset_eq!やる方法探す
-
-        let mut pedometer = Pedometer::new();
+        let mut pedometer = ThresholdDetector::new();
         let composite_accel = 2.0;
 
-        for count in 1..50 {
-            Pedometer::set_threshold(&mut pedometer, composite_accel);
+        // 50サンプルまでは合計するだけで閾値は変わらない
+        for count in 1..=50 {
+            ThresholdDetector::set_threshold(&mut pedometer, composite_accel);
 
-            if count == 50 {
-                // assert_eq!(pedometer.threshold, 2.0);
-                // assert_eq!(pedometer.hysteresis, 0.4);
-                // assert_eq!(pedometer.total_composite_accel, 0.0);
-                assert_eq!(pedometer.sample_count, 0);
-            } else {
-                // assert_eq!(pedometer.threshold, 1.5);
-                // assert_eq!(pedometer.hysteresis, 0.15);
-                // assert_eq!(pedometer.total_composite_accel, 2.0 * count as f32);
-                assert_eq!(pedometer.sample_count, count);
-            }
+            assert_approx_eq(pedometer.threshold, 1.5);
+            assert_approx_eq(pedometer.hysteresis, 0.15);
+            assert_approx_eq(pedometer.total_composite_accel, 2.0 * count as f32);
+            assert_eq!(pedometer.sample_count, count);
         }
+
+        ThresholdDetector::set_threshold(&mut pedometer, composite_accel);
+
+        assert_approx_eq(pedometer.threshold, 2.0);
+        assert_approx_eq(pedometer.hysteresis, 0.4);
+        assert_approx_eq(pedometer.total_composite_accel, 0.0);
+        assert_eq!(pedometer.sample_count, 0);
     }
 
     #[test]
     fn test_set_state() {
-        let mut pedometer1 = Pedometer::new();
+        let mut pedometer1 = ThresholdDetector::new();
         let large_composite_accel = 2.0;
 
-        Pedometer::set_state(&mut pedometer1, large_composite_accel);
+        ThresholdDetector::set_state(&mut pedometer1, large_composite_accel);
 
         assert!(pedometer1.state);
 
-        let mut pedometer2 = Pedometer::new();
+        let mut pedometer2 = ThresholdDetector::new();
         let small_composite_accel = 1.0;
 
-        Pedometer::set_state(&mut pedometer2, small_composite_accel);
+        ThresholdDetector::set_state(&mut pedometer2, small_composite_accel);
 
         assert!(!pedometer2.state);
     }
 
     #[test]
-    fn test_set_step_count() {
-        let mut pedometer1 = Pedometer::new();
+    fn test_count_step() {
+        let mut pedometer1 = ThresholdDetector::new();
         pedometer1.last_state = false;
         pedometer1.state = false;
 
-        Pedometer::set_step_count(&mut pedometer1);
-
-        assert_eq!(pedometer1.step_count, 0);
+        assert!(!ThresholdDetector::count_step(&mut pedometer1));
         assert!(!pedometer1.last_state);
 
-        let mut pedometer2 = Pedometer::new();
+        let mut pedometer2 = ThresholdDetector::new();
         pedometer2.last_state = false;
         pedometer2.state = true;
 
-        Pedometer::set_step_count(&mut pedometer2);
-
-        assert_eq!(pedometer2.step_count, 1);
+        assert!(ThresholdDetector::count_step(&mut pedometer2));
         assert!(pedometer2.last_state);
 
-        let mut pedometer3 = Pedometer::new();
+        let mut pedometer3 = ThresholdDetector::new();
         pedometer3.last_state = true;
         pedometer3.state = true;
 
-        Pedometer::set_step_count(&mut pedometer3);
-
-        assert_eq!(pedometer3.step_count, 0);
+        assert!(!ThresholdDetector::count_step(&mut pedometer3));
         assert!(pedometer3.last_state);
 
-        let mut pedometer4 = Pedometer::new();
+        let mut pedometer4 = ThresholdDetector::new();
         pedometer4.last_state = true;
         pedometer4.state = false;
 
-        Pedometer::set_step_count(&mut pedometer4);
-
-        assert_eq!(pedometer4.step_count, 0);
+        assert!(!ThresholdDetector::count_step(&mut pedometer4));
         assert!(!pedometer4.last_state);
     }
+
+    // 一定の周波数で揺れる加速度をサンプリングして、検出した歩数を数える
+    fn count_steps<D: StepDetector>(
+        detector: &mut D,
+        frequency: f32,
+        amplitude: f32,
+        duration_ms: u32,
+    ) -> i32 {
+        const DT_MS: u32 = 20;
+
+        (0..duration_ms / DT_MS)
+            .map(|index| {
+                let t = (index * DT_MS) as f32 / 1000.0;
+                let z = 1.0 + amplitude * (2.0 * core::f32::consts::PI * frequency * t).sin();
+                detector.detect(F32x3::new(0.0, 0.0, z), DT_MS)
+            })
+            .sum()
+    }
+
+    // 歩き始めを確かめずに、1歩ずつすぐ数える
+    fn every_step() -> Detector {
+        Detector::Peak(PeakDetector {
+            min_regular_steps: 1,
+            ..PeakDetector::new()
+        })
+    }
+
+    // 間隔(ミリ秒)ごとに山を1つ作って、山ごとに数えた歩数を返す
+    fn detect_peaks(detector: &mut PeakDetector, intervals_ms: &[u32]) -> Vec<i32> {
+        intervals_ms
+            .iter()
+            .map(|interval_ms| {
+                detector.detect(F32x3::new(0.0, 0.0, 1.5), interval_ms - 100)
+                    + detector.detect(F32x3::new(0.0, 0.0, 1.0), 100)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_peak_detector_walk() {
+        // 2Hzで10秒歩くと20歩になる
+        assert_eq!(count_steps(&mut PeakDetector::new(), 2.0, 0.3, 10000), 20);
+        // ゆっくり小さく揺れる歩き方でも数える
+        assert_eq!(count_steps(&mut PeakDetector::new(), 1.0, 0.15, 10000), 10);
+    }
+
+    #[test]
+    fn test_peak_detector_ignores_vibration() {
+        // 乗り物の細かい振動は数えない
+        assert_eq!(count_steps(&mut PeakDetector::new(), 12.5, 0.3, 10000), 0);
+        // 小さすぎる揺れは数えない
+        assert_eq!(count_steps(&mut PeakDetector::new(), 2.0, 0.02, 10000), 0);
+    }
+
+    #[test]
+    fn test_peak_detector_regularity() {
+        let mut detector = PeakDetector::new();
+
+        // 間隔の揃った山が5つ続くまでは数えずに溜めておき、5つ目でまとめて数える
+        assert_eq!(detect_peaks(&mut detector, &[600; 6]), [0, 0, 0, 0, 5, 1]);
+        // 間隔が空いたら、また歩き始めを確かめてから数える
+        assert_eq!(detect_peaks(&mut detector, &[3000, 600]), [0, 0]);
+
+        // 車の段差のように間隔がばらばらな揺れは数えない
+        let mut detector = PeakDetector::new();
+
+        assert_eq!(detect_peaks(&mut detector, &[600, 400, 1500, 500, 1400, 300, 1200]), [0; 7]);
+    }
+
+    #[test]
+    fn test_detector_kind() {
+        for kind in [DetectorKind::Threshold, DetectorKind::Peak] {
+            assert_eq!(Detector::new(kind).kind(), kind);
+            assert_eq!(DetectorKind::from_i32(kind.to_i32()), kind);
+        }
+        assert_eq!(DetectorKind::from_i32(-1), DetectorKind::default());
+    }
+
+    #[test]
+    fn test_pedometer_update() {
+        let mut pedometer = Pedometer::with_detector(every_step());
+
+        for z in [1.0, 1.5, 1.0] {
+            pedometer.update(F32x3::new(0.0, 0.0, z), 100);
+        }
+
+        assert_eq!(pedometer.step_count, 1);
+        assert_eq!(pedometer.history.days.totals[0], 1);
+    }
+
+    #[test]
+    fn test_pedometer_push() {
+        let mut pedometer = Pedometer::with_detector(every_step());
+        // 時刻が一周しても経過時間は100ミリ秒ずつになる
+        let start_ms = u32::MAX - 99;
+
+        for (index, z) in [1.0, 1.5, 1.0, 1.5, 1.0].iter().enumerate() {
+            pedometer.push(Sample {
+                timestamp_ms: start_ms.wrapping_add(index as u32 * 100),
+                accel: F32x3::new(0.0, 0.0, *z),
+            });
+        }
+
+        // 2つ目の山は前の歩から200ミリ秒しか経っていないので数えない
+        assert_eq!(pedometer.step_count, 1);
+        assert_eq!(pedometer.last_timestamp_ms, Some(300));
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::<3>::new();
+
+        history.add(1);
+        history.advance(1);
+        history.add(2);
+
+        assert_eq!(history.totals(), [0, 1, 2]);
+
+        history.advance(1);
+        history.add(3);
+        history.advance(1);
+        history.add(4);
+
+        // 一番古い期間から上書きされる
+        assert_eq!(history.totals(), [2, 3, 4]);
+
+        history.advance(10);
+
+        assert_eq!(history.totals(), [0, 0, 0]);
+        assert_eq!(History::from_totals([2, 3, 4]).totals(), [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_step_history_advance() {
+        let mut history = StepHistory::new();
+        let time = DateTime { hour: 23, minute: 30, ..DateTime::default() };
+        history.add(5);
+
+        // 30分後には日も時間も変わる
+        let now = DateTime::from_seconds(time.seconds() + 30 * 60);
+        history.advance(&time, &now);
+        history.add(1);
+
+        assert_eq!(history.days.totals()[DAYS_OF_HISTORY - 2..], [5, 1]);
+        assert_eq!(history.hours.totals()[HOURS_OF_HISTORY - 2..], [5, 1]);
+
+        // 時計が戻っても履歴は変わらない
+        history.advance(&now, &time);
+
+        assert_eq!(history.days.totals()[DAYS_OF_HISTORY - 1], 1);
+    }
 }
@@ -0,0 +1,201 @@
+use crate::settings::MIN_BRIGHTNESS;
+
+// 操作が無いまま画面を暗くするまでの時間(ミリ秒)
+pub const DIM_AFTER_MS: u32 = 30_000;
+// 操作が無いまま画面を消すまでの時間(ミリ秒)
+pub const OFF_AFTER_MS: u32 = 60_000;
+// この時間のうちにWAKE_STEPS歩あるいたら画面を点ける(ミリ秒)
+pub const BURST_WINDOW_MS: u32 = 5_000;
+pub const WAKE_STEPS: i32 = 8;
+
+// 画面の電源の状態
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerState {
+    On,
+    Dimmed,
+    Off,
+}
+
+// 次の周回までマイコンがどう待つか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wait {
+    // 画面を描き続けるので、メインループの間隔ごとに起きる
+    Interval(u32),
+    // 画面が消えているので、加速度センサのサンプルが溜まるかボタンが押されるまで深く眠る
+    Standby,
+}
+
+impl Wait {
+    // 待っている間に経った時間。深く眠っている間はメインループのタイマーが止まるので、
+    // その間に溜まったサンプルの時間で数える
+    pub fn elapsed_ms(self, sampled_ms: u32) -> u32 {
+        match self {
+            Wait::Interval(interval_ms) => interval_ms,
+            Wait::Standby => sampled_ms,
+        }
+    }
+}
+
+// 操作が無い時間を数えて、画面を暗くしたり消したりする。
+// ボタンを押すか、続けて歩き始めたら点け直す
+pub struct PowerManager {
+    pub state: PowerState,
+    // 最後に操作してからの時間
+    pub idle_ms: u32,
+    // 歩き始めてから数えている歩数と時間
+    pub burst_steps: i32,
+    pub burst_ms: u32,
+}
+
+impl Default for PowerManager {
+    fn default() -> Self {
+        PowerManager::new()
+    }
+}
+
+impl PowerManager {
+    pub fn new() -> PowerManager {
+        PowerManager {
+            state: PowerState::On,
+            idle_ms: 0,
+            burst_steps: 0,
+            burst_ms: 0,
+        }
+    }
+    // 時間を進めて、操作が無い時間に合わせて画面を暗くする
+    pub fn tick(&mut self, dt_ms: u32) {
+        self.idle_ms = self.idle_ms.saturating_add(dt_ms);
+        self.state = if self.idle_ms >= OFF_AFTER_MS {
+            PowerState::Off
+        } else if self.idle_ms >= DIM_AFTER_MS {
+            PowerState::Dimmed
+        } else {
+            PowerState::On
+        };
+
+        if self.burst_steps > 0 {
+            self.burst_ms = self.burst_ms.saturating_add(dt_ms);
+            if self.burst_ms > BURST_WINDOW_MS {
+                self.burst_steps = 0;
+                self.burst_ms = 0;
+            }
+        }
+    }
+    // 操作があったので画面を点ける。点いていなかったらtrueを返す
+    pub fn wake(&mut self) -> bool {
+        let woke = self.state != PowerState::On;
+        self.state = PowerState::On;
+        self.idle_ms = 0;
+        woke
+    }
+    // 歩いた歩数を数え、続けて歩いていたら画面を点ける
+    pub fn walk(&mut self, steps: i32) {
+        self.burst_steps += steps;
+        if self.burst_steps >= WAKE_STEPS {
+            self.burst_steps = 0;
+            self.burst_ms = 0;
+            self.wake();
+        }
+    }
+    // 画面が見えているか。消えている間は描いても見えないので描画を省く
+    pub fn is_visible(&self) -> bool {
+        self.state != PowerState::Off
+    }
+    // 画面が消えている間だけ深く眠る。点いている間はinterval_msごとに起きて描き直す
+    pub fn wait(&self, interval_ms: u32) -> Wait {
+        match self.state {
+            PowerState::On | PowerState::Dimmed => Wait::Interval(interval_ms),
+            PowerState::Off => Wait::Standby,
+        }
+    }
+    // 設定の明るさに対するバックライトの明るさ。0なら消す
+    pub fn backlight(&self, brightness: i32) -> i32 {
+        match self.state {
+            PowerState::On => brightness,
+            PowerState::Dimmed => MIN_BRIGHTNESS,
+            PowerState::Off => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::MAX_BRIGHTNESS;
+
+    #[test]
+    fn test_timeout() {
+        let mut power = PowerManager::new();
+
+        power.tick(DIM_AFTER_MS - 1);
+        assert_eq!(power.state, PowerState::On);
+        assert_eq!(power.backlight(MAX_BRIGHTNESS), MAX_BRIGHTNESS);
+        assert!(power.is_visible());
+
+        power.tick(1);
+        assert_eq!(power.state, PowerState::Dimmed);
+        assert_eq!(power.backlight(MAX_BRIGHTNESS), MIN_BRIGHTNESS);
+        assert!(power.is_visible());
+
+        power.tick(OFF_AFTER_MS - DIM_AFTER_MS);
+        assert_eq!(power.state, PowerState::Off);
+        assert_eq!(power.backlight(MAX_BRIGHTNESS), 0);
+        assert!(!power.is_visible());
+
+        // 消えたままでいる
+        power.tick(u32::MAX);
+        assert_eq!(power.state, PowerState::Off);
+    }
+
+    #[test]
+    fn test_wake() {
+        let mut power = PowerManager::new();
+
+        assert!(!power.wake());
+
+        power.tick(OFF_AFTER_MS);
+        assert!(power.wake());
+        assert_eq!(power.state, PowerState::On);
+
+        // 点け直してから数え直す
+        power.tick(DIM_AFTER_MS - 1);
+        assert_eq!(power.state, PowerState::On);
+    }
+
+    #[test]
+    fn test_wait() {
+        let mut power = PowerManager::new();
+
+        assert_eq!(power.wait(100), Wait::Interval(100));
+        assert_eq!(power.wait(100).elapsed_ms(500), 100);
+
+        power.tick(DIM_AFTER_MS);
+        assert_eq!(power.wait(100), Wait::Interval(100));
+
+        // 画面が消えたら深く眠り、溜まったサンプルの分だけ時間を進める
+        power.tick(OFF_AFTER_MS);
+        assert_eq!(power.wait(100), Wait::Standby);
+        assert_eq!(power.wait(100).elapsed_ms(500), 500);
+
+        power.wake();
+        assert_eq!(power.wait(100), Wait::Interval(100));
+    }
+
+    #[test]
+    fn test_wake_on_steps() {
+        let mut power = PowerManager::new();
+        power.tick(OFF_AFTER_MS);
+
+        // 間をあけて数歩ずつ歩いても点かない
+        power.walk(WAKE_STEPS - 1);
+        power.tick(BURST_WINDOW_MS + 1);
+        power.walk(1);
+        assert_eq!(power.state, PowerState::Off);
+
+        // 続けて歩いたら点く
+        power.tick(1000);
+        power.walk(WAKE_STEPS - 1);
+        assert_eq!(power.state, PowerState::On);
+        assert_eq!(power.idle_ms, 0);
+    }
+}
@@ -0,0 +1,185 @@
+// ケイデンスに応じて歩幅と消費カロリーを変える範囲。
+// この範囲より遅ければ歩き、速ければ走りの値を使い、間は比例させる
+const WALKING_CADENCE: i32 = 100;
+const RUNNING_CADENCE: i32 = 170;
+// 身長に対する歩幅の割合(千分率)
+const WALKING_STRIDE_RATIO: i32 = 415;
+const RUNNING_STRIDE_RATIO: i32 = 650;
+// 体重1kgで1km進んだときの消費カロリー(cal)
+const WALKING_CALORIES_PER_KG_KM: i32 = 500;
+const RUNNING_CALORIES_PER_KG_KM: i32 = 1000;
+const CENTIMETERS_PER_KILOMETER: i32 = 100_000;
+// 設定で変えられる体格の範囲
+pub const MIN_HEIGHT_CM: i32 = 100;
+pub const MAX_HEIGHT_CM: i32 = 220;
+pub const MIN_WEIGHT_KG: i32 = 20;
+pub const MAX_WEIGHT_KG: i32 = 200;
+pub const MIN_STRIDE_CM: i32 = 30;
+pub const MAX_STRIDE_CM: i32 = 150;
+
+// 歩いた距離とカロリーの計算に使う持ち主の体格
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    pub height_cm: i32,
+    pub weight_kg: i32,
+    // 実際に測った歩いているときの歩幅。無ければ身長から求める
+    pub stride_cm: Option<i32>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile::new(170, 60, None)
+    }
+}
+
+impl Profile {
+    pub fn new(height_cm: i32, weight_kg: i32, stride_cm: Option<i32>) -> Profile {
+        Profile {
+            height_cm,
+            weight_kg,
+            stride_cm,
+        }
+    }
+    pub fn change_height(&mut self, height_cm: i32) {
+        self.height_cm = (self.height_cm + height_cm).clamp(MIN_HEIGHT_CM, MAX_HEIGHT_CM);
+    }
+    pub fn change_weight(&mut self, weight_kg: i32) {
+        self.weight_kg = (self.weight_kg + weight_kg).clamp(MIN_WEIGHT_KG, MAX_WEIGHT_KG);
+    }
+    // 測った歩幅を変える。無ければ身長から求めた歩幅から変え、一番短くしたところから減らすと身長から求めるのに戻す
+    pub fn change_stride(&mut self, stride_cm: i32) {
+        self.stride_cm = match self.stride_cm {
+            Some(current) if current + stride_cm < MIN_STRIDE_CM => None,
+            Some(current) => Some((current + stride_cm).min(MAX_STRIDE_CM)),
+            None if stride_cm > 0 => Some(self.stride_cm(0).clamp(MIN_STRIDE_CM, MAX_STRIDE_CM)),
+            None => None,
+        };
+    }
+    // 1歩の歩幅(cm)。速く歩くほど歩幅が広がる
+    pub fn stride_cm(&self, cadence: i32) -> i32 {
+        let ratio = interpolate(cadence, WALKING_STRIDE_RATIO, RUNNING_STRIDE_RATIO);
+
+        match self.stride_cm {
+            // 測った歩幅は歩いているときのものとして、同じ割合で広げる
+            Some(stride_cm) => stride_cm * ratio / WALKING_STRIDE_RATIO,
+            None => self.height_cm * ratio / 1000,
+        }
+    }
+    // 歩いた距離での消費カロリー(cal)
+    pub fn calories(&self, distance_cm: i32, cadence: i32) -> i32 {
+        let calories_per_kg_km = interpolate(cadence, WALKING_CALORIES_PER_KG_KM, RUNNING_CALORIES_PER_KG_KM);
+
+        (calories_per_kg_km as i64 * self.weight_kg as i64 * distance_cm as i64
+            / CENTIMETERS_PER_KILOMETER as i64) as i32
+    }
+}
+
+// ケイデンスに応じて歩きの値から走りの値まで比例させる
+fn interpolate(cadence: i32, walking: i32, running: i32) -> i32 {
+    let cadence = cadence.clamp(WALKING_CADENCE, RUNNING_CADENCE);
+
+    walking + (running - walking) * (cadence - WALKING_CADENCE) / (RUNNING_CADENCE - WALKING_CADENCE)
+}
+
+// 今日歩いた距離と消費カロリー。1歩ごとにその時のケイデンスで足していく
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Exercise {
+    pub distance_cm: i32,
+    pub calories: i32,
+}
+
+impl Exercise {
+    pub fn new() -> Exercise {
+        Exercise {
+            distance_cm: 0,
+            calories: 0,
+        }
+    }
+    pub fn add_step(&mut self, profile: &Profile, cadence: i32) {
+        let stride_cm = profile.stride_cm(cadence);
+
+        self.distance_cm += stride_cm;
+        self.calories += profile.calories(stride_cm, cadence);
+    }
+    // 日付が変わったら0から数え直す
+    pub fn reset(&mut self) {
+        *self = Exercise::new();
+    }
+    // 表示用に丸めた距離(m)とカロリー(kcal)
+    pub fn distance_m(&self) -> i32 {
+        self.distance_cm / 100
+    }
+    pub fn kilocalories(&self) -> i32 {
+        self.calories / 1000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stride() {
+        let profile = Profile::default();
+
+        assert_eq!(profile.stride_cm(0), 70);
+        assert_eq!(profile.stride_cm(100), 70);
+        assert_eq!(profile.stride_cm(135), 90);
+        assert_eq!(profile.stride_cm(200), 110);
+
+        let profile = Profile::new(170, 60, Some(83));
+
+        assert_eq!(profile.stride_cm(100), 83);
+        assert_eq!(profile.stride_cm(170), 130);
+    }
+
+    #[test]
+    fn test_change_profile() {
+        let mut profile = Profile::default();
+
+        profile.change_height(-100);
+        profile.change_weight(1);
+
+        assert_eq!(profile.height_cm, MIN_HEIGHT_CM);
+        assert_eq!(profile.weight_kg, 61);
+
+        // 身長から求めた歩幅から測った歩幅に切り替えて変える
+        profile.change_stride(-1);
+        assert_eq!(profile.stride_cm, None);
+        profile.change_stride(1);
+        assert_eq!(profile.stride_cm, Some(41));
+        profile.change_stride(1);
+        assert_eq!(profile.stride_cm, Some(42));
+
+        // 一番短くしたところから減らすと身長から求めるのに戻る
+        profile.stride_cm = Some(MIN_STRIDE_CM);
+        profile.change_stride(-1);
+        assert_eq!(profile.stride_cm, None);
+    }
+
+    #[test]
+    fn test_calories() {
+        let profile = Profile::default();
+
+        // 歩きで1km、走りで1km
+        assert_eq!(profile.calories(100_000, 100), 30_000);
+        assert_eq!(profile.calories(100_000, 170), 60_000);
+    }
+
+    #[test]
+    fn test_exercise() {
+        let profile = Profile::default();
+        let mut exercise = Exercise::new();
+
+        for _ in 0..10000 {
+            exercise.add_step(&profile, 100);
+        }
+
+        assert_eq!(exercise.distance_m(), 7000);
+        assert_eq!(exercise.kilocalories(), 210);
+
+        exercise.reset();
+
+        assert_eq!(exercise, Exercise::new());
+    }
+}
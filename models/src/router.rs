@@ -4,6 +4,7 @@ pub enum Route {
     Meal,
     Play,
     Game,
+    Stats,
 }
 
 pub struct Router {
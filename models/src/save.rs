@@ -0,0 +1,709 @@
+use crate::{
+    clock::DateTime,
+    crc::crc32,
+    event::Event,
+    game::Game,
+    goal::DEFAULT_GOAL_STEP_COUNT,
+    needs::MAX_NEED,
+    pedometer::{
+        DetectorKind,
+        History,
+        DAYS_OF_HISTORY,
+    },
+    profile::{
+        Exercise,
+        Profile,
+    },
+    settings::{
+        ThemeMode,
+        MAX_BRIGHTNESS,
+        MIN_BRIGHTNESS,
+    },
+    storage::Storage,
+};
+
+// レコードの大きさ。項目を増やしても収まるように余裕を持たせている
+pub const RECORD_SIZE: usize = 128;
+// 現在のレコードのバージョン
+pub const VERSION: u8 = 9;
+
+const MAGIC: [u8; 2] = *b"PK";
+// マジック(2) + バージョン(1) + ペイロード長(1)
+const HEADER_SIZE: usize = 4;
+const CRC_SIZE: usize = 4;
+const MAX_PAYLOAD_SIZE: usize = RECORD_SIZE - HEADER_SIZE - CRC_SIZE;
+
+#[derive(Debug, PartialEq)]
+pub enum SaveError<E> {
+    // 読み書き先のエラー
+    Storage(E),
+    // 何も保存されていない
+    Empty,
+    // マジックかCRCが一致しない
+    Corrupted,
+    // 読み込めないバージョン
+    UnsupportedVersion(u8),
+}
+
+// 電源を切っても残したい状態
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SaveData {
+    pub step_count: i32,
+    pub intimacy: i32,
+    pub rice_ball_amount: i32,
+    pub rice_ball_last_step_count: i32,
+    pub shuriken_amount: i32,
+    pub shuriken_last_step_count: i32,
+    pub hunger: i32,
+    pub happiness: i32,
+    pub energy: i32,
+    pub time: DateTime,
+    pub day_start_step_count: i32,
+    // 日ごとの歩数を古い順に並べたもの。時間ごとの歩数は保存しない
+    pub days: [i32; DAYS_OF_HISTORY],
+    // 今日達成した目標の数。再起動しても同じご褒美をもらわないようにする
+    pub goals_achieved: i32,
+    pub profile: Profile,
+    pub exercise: Exercise,
+    pub theme: ThemeMode,
+    pub brightness: i32,
+    pub detector: DetectorKind,
+    // 1日の目標歩数。それぞれの目標はここから決まる
+    pub goal_step_count: i32,
+}
+
+impl SaveData {
+    pub fn from_game(game: &Game) -> SaveData {
+        SaveData {
+            step_count: game.pedometer.step_count,
+            intimacy: game.character.intimacy,
+            rice_ball_amount: game.rice_ball.amount,
+            rice_ball_last_step_count: game.rice_ball.last_step_count,
+            shuriken_amount: game.shuriken.amount,
+            shuriken_last_step_count: game.shuriken.last_step_count,
+            hunger: game.needs.hunger,
+            happiness: game.needs.happiness,
+            energy: game.needs.energy,
+            time: game.time,
+            day_start_step_count: game.day_start_step_count,
+            days: game.pedometer.history.days.totals(),
+            goals_achieved: game.goals.achieved as i32,
+            profile: game.profile,
+            exercise: game.exercise,
+            theme: game.settings.theme,
+            brightness: game.settings.brightness,
+            detector: game.settings.detector,
+            goal_step_count: game.goals.step_count(),
+        }
+    }
+    pub fn restore(&self, game: &mut Game) {
+        game.pedometer.step_count = self.step_count;
+        game.character.intimacy = self.intimacy;
+        game.rice_ball.amount = self.rice_ball_amount;
+        game.rice_ball.last_step_count = self.rice_ball_last_step_count;
+        game.shuriken.amount = self.shuriken_amount;
+        game.shuriken.last_step_count = self.shuriken_last_step_count;
+        game.needs.hunger = self.hunger;
+        game.needs.happiness = self.happiness;
+        game.needs.energy = self.energy;
+        game.time = self.time;
+        game.day_start_step_count = self.day_start_step_count;
+        game.pedometer.history.days = History::from_totals(self.days);
+        game.goals.set_step_count(self.goal_step_count);
+        game.goals.achieved = (self.goals_achieved.max(0) as usize).min(game.goals.goals.len());
+        game.profile = self.profile;
+        game.exercise = self.exercise;
+        game.settings.theme = self.theme;
+        game.settings.brightness = self.brightness.clamp(MIN_BRIGHTNESS, MAX_BRIGHTNESS);
+        game.settings.detector = self.detector;
+        game.update_theme();
+        game.update_detector();
+    }
+    pub fn load<S: Storage>(storage: &mut S) -> Result<SaveData, SaveError<S::Error>> {
+        let mut record = [0; RECORD_SIZE];
+        storage.read(&mut record).map_err(SaveError::Storage)?;
+        Self::decode(&record)
+    }
+    pub fn save<S: Storage>(&self, storage: &mut S) -> Result<(), SaveError<S::Error>> {
+        storage.write(&self.encode()).map_err(SaveError::Storage)
+    }
+    // マジック、バージョン、ペイロード長、ペイロード、CRCの順に並べる。
+    // 残りは消去済みのフラッシュと同じ0xFFで埋める。
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut record = [0xFF; RECORD_SIZE];
+        let mut payload = Writer::new(&mut record[HEADER_SIZE..HEADER_SIZE + MAX_PAYLOAD_SIZE]);
+
+        payload.write(self.step_count);
+        payload.write(self.intimacy);
+        payload.write(self.rice_ball_amount);
+        payload.write(self.rice_ball_last_step_count);
+        payload.write(self.shuriken_amount);
+        payload.write(self.shuriken_last_step_count);
+        payload.write(self.hunger);
+        payload.write(self.happiness);
+        payload.write(self.energy);
+        payload.write(self.time.seconds() as i32);
+        payload.write(self.day_start_step_count);
+        for total in self.days.iter() {
+            payload.write(*total);
+        }
+        payload.write(self.goals_achieved);
+        payload.write(self.profile.height_cm);
+        payload.write(self.profile.weight_kg);
+        // 歩幅を測っていなければ0にする
+        payload.write(self.profile.stride_cm.unwrap_or(0));
+        payload.write(self.exercise.distance_cm);
+        payload.write(self.exercise.calories);
+        payload.write(self.theme.to_i32());
+        payload.write(self.brightness);
+        payload.write(self.detector.to_i32());
+        payload.write(self.goal_step_count);
+
+        let length = payload.position;
+        record[..2].copy_from_slice(&MAGIC);
+        record[2] = VERSION;
+        record[3] = length as u8;
+
+        let crc_position = HEADER_SIZE + length;
+        let crc = crc32(&record[..crc_position]);
+        record[crc_position..crc_position + CRC_SIZE].copy_from_slice(&crc.to_le_bytes());
+
+        record
+    }
+    pub fn decode<E>(record: &[u8; RECORD_SIZE]) -> Result<SaveData, SaveError<E>> {
+        if record.iter().all(|byte| *byte == 0xFF) {
+            return Err(SaveError::Empty);
+        }
+        if record[..2] != MAGIC {
+            return Err(SaveError::Corrupted);
+        }
+
+        let version = record[2];
+        let length = record[3] as usize;
+        if length > MAX_PAYLOAD_SIZE {
+            return Err(SaveError::Corrupted);
+        }
+
+        let crc_position = HEADER_SIZE + length;
+        let mut crc = [0; CRC_SIZE];
+        crc.copy_from_slice(&record[crc_position..crc_position + CRC_SIZE]);
+        if u32::from_le_bytes(crc) != crc32(&record[..crc_position]) {
+            return Err(SaveError::Corrupted);
+        }
+
+        Self::migrate(version, &record[HEADER_SIZE..crc_position])
+    }
+    // 古いバージョンのペイロードを現在のSaveDataに変換する
+    fn migrate<E>(version: u8, payload: &[u8]) -> Result<SaveData, SaveError<E>> {
+        match version {
+            1..=VERSION => Self::decode_payload(version, payload).ok_or(SaveError::Corrupted),
+            _ => Err(SaveError::UnsupportedVersion(version)),
+        }
+    }
+    // バージョンごとに追加された項目を順に読み、無い項目は初期値にする
+    fn decode_payload(version: u8, payload: &[u8]) -> Option<SaveData> {
+        let mut payload = Reader::new(payload);
+
+        let mut save_data = SaveData {
+            step_count: payload.read()?,
+            intimacy: payload.read()?,
+            rice_ball_amount: payload.read()?,
+            rice_ball_last_step_count: payload.read()?,
+            shuriken_amount: payload.read()?,
+            shuriken_last_step_count: payload.read()?,
+            hunger: MAX_NEED,
+            happiness: MAX_NEED,
+            energy: MAX_NEED,
+            time: DateTime::default(),
+            day_start_step_count: 0,
+            days: [0; DAYS_OF_HISTORY],
+            goals_achieved: 0,
+            profile: Profile::default(),
+            exercise: Exercise::new(),
+            theme: ThemeMode::default(),
+            brightness: MAX_BRIGHTNESS,
+            detector: DetectorKind::default(),
+            goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+        };
+
+        // v2で欲求を追加した
+        if version >= 2 {
+            save_data.hunger = payload.read()?;
+            save_data.happiness = payload.read()?;
+            save_data.energy = payload.read()?;
+        }
+
+        // v3で日時と今日の始まりの歩数を追加した。それより前は今日を0歩から始める
+        if version >= 3 {
+            save_data.time = DateTime::from_seconds(payload.read()? as u32);
+            save_data.day_start_step_count = payload.read()?;
+        } else {
+            save_data.day_start_step_count = save_data.step_count;
+        }
+
+        // v4で日ごとの歩数を追加した。それより前は今日の分だけ分かる
+        if version >= 4 {
+            for total in save_data.days.iter_mut() {
+                *total = payload.read()?;
+            }
+        } else {
+            save_data.days[DAYS_OF_HISTORY - 1] =
+                save_data.step_count - save_data.day_start_step_count;
+        }
+
+        // v5で今日達成した目標の数を追加した
+        if version >= 5 {
+            save_data.goals_achieved = payload.read()?;
+        }
+
+        // v6で体格と今日歩いた距離、消費カロリーを追加した
+        if version >= 6 {
+            let height_cm = payload.read()?;
+            let weight_kg = payload.read()?;
+            let stride_cm = Some(payload.read()?).filter(|stride_cm| *stride_cm > 0);
+            save_data.profile = Profile::new(height_cm, weight_kg, stride_cm);
+            save_data.exercise = Exercise {
+                distance_cm: payload.read()?,
+                calories: payload.read()?,
+            };
+        }
+
+        // v7で画面の色の設定を追加した
+        if version >= 7 {
+            save_data.theme = ThemeMode::from_i32(payload.read()?);
+        }
+
+        // v8でバックライトの明るさを追加した
+        if version >= 8 {
+            save_data.brightness = payload.read()?;
+        }
+
+        // v9で歩数の検出アルゴリズムと1日の目標歩数を追加した
+        if version >= 9 {
+            save_data.detector = DetectorKind::from_i32(payload.read()?);
+            save_data.goal_step_count = payload.read()?;
+        }
+
+        Some(save_data)
+    }
+}
+
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(buffer: &'a mut [u8]) -> Writer<'a> {
+        Writer { buffer, position: 0 }
+    }
+    fn write(&mut self, value: i32) {
+        self.buffer[self.position..self.position + 4].copy_from_slice(&value.to_le_bytes());
+        self.position += 4;
+    }
+}
+
+struct Reader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buffer: &'a [u8]) -> Reader<'a> {
+        Reader { buffer, position: 0 }
+    }
+    fn read(&mut self) -> Option<i32> {
+        let bytes = self.buffer.get(self.position..self.position + 4)?;
+        let mut value = [0; 4];
+        value.copy_from_slice(bytes);
+        self.position += 4;
+        Some(i32::from_le_bytes(value))
+    }
+}
+
+// 食事、遊び、日付の変わり目、目標の達成、設定の変更、一定歩数ごとにセーブするかどうかを決める。
+// 時計は電源を切ると止まるので、1時間ごとにもセーブして最後の日時から数え直せるようにする
+pub struct Autosave {
+    pub interval: i32,
+    pub last_step_count: i32,
+    // 前のセーブに失敗したので、次の出来事でセーブし直す
+    pub pending: bool,
+}
+
+impl Autosave {
+    pub fn new(interval: i32) -> Autosave {
+        Autosave {
+            interval,
+            last_step_count: 0,
+            pending: false,
+        }
+    }
+    pub fn check(&mut self, event: Event, step_count: i32) -> bool {
+        let should_save = self.pending || match event {
+            Event::Ate(_)
+            | Event::GameWon
+            | Event::GameLost
+            | Event::DayChanged
+            | Event::GoalReached(_)
+            | Event::HourPassed
+            | Event::SettingsChanged => true,
+            Event::Walked(_) => step_count - self.last_step_count >= self.interval,
+            _ => false,
+        };
+
+        if should_save {
+            self.last_step_count = step_count;
+        }
+
+        should_save
+    }
+    // セーブの結果を受け取る。失敗したら次の出来事でもう一度セーブする
+    pub fn finish<E>(&mut self, result: Result<(), E>) -> Result<(), E> {
+        self.pending = result.is_err();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        goal::Goals,
+        storage::MemoryStorage,
+    };
+    use core::convert::Infallible;
+
+    fn save_data() -> SaveData {
+        SaveData {
+            step_count: 1234,
+            intimacy: 56,
+            rice_ball_amount: 7,
+            rice_ball_last_step_count: 1230,
+            shuriken_amount: -1,
+            shuriken_last_step_count: 1220,
+            hunger: 80,
+            happiness: 60,
+            energy: 40,
+            time: DateTime {
+                year: 2021,
+                month: 5,
+                day: 6,
+                hour: 7,
+                minute: 8,
+                second: 9,
+            },
+            day_start_step_count: 1000,
+            days: [100, 0, 300, 400, 500, 600, 234],
+            goals_achieved: 1,
+            profile: Profile::new(160, 50, Some(65)),
+            exercise: Exercise {
+                distance_cm: 16_380,
+                calories: 4_095,
+            },
+            theme: ThemeMode::Dark,
+            brightness: 2,
+            detector: DetectorKind::Threshold,
+            goal_step_count: 8000,
+        }
+    }
+
+    #[test]
+    fn test_encode_and_decode() {
+        let record = save_data().encode();
+
+        assert_eq!(&record[..4], &[b'P', b'K', VERSION, 112]);
+        assert_eq!(SaveData::decode::<Infallible>(&record), Ok(save_data()));
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        let record = [0xFF; RECORD_SIZE];
+
+        assert_eq!(SaveData::decode::<Infallible>(&record), Err(SaveError::Empty));
+    }
+
+    #[test]
+    fn test_decode_corrupted() {
+        let mut record = save_data().encode();
+        record[5] ^= 0x01;
+
+        assert_eq!(SaveData::decode::<Infallible>(&record), Err(SaveError::Corrupted));
+
+        let mut record = save_data().encode();
+        record[0] = b'X';
+
+        assert_eq!(SaveData::decode::<Infallible>(&record), Err(SaveError::Corrupted));
+
+        let mut record = save_data().encode();
+        record[3] = 0xFF;
+
+        assert_eq!(SaveData::decode::<Infallible>(&record), Err(SaveError::Corrupted));
+    }
+
+    #[test]
+    fn test_decode_unsupported_version() {
+        let mut record = save_data().encode();
+        record[2] = VERSION + 1;
+        let crc = crc32(&record[..116]);
+        record[116..120].copy_from_slice(&crc.to_le_bytes());
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Err(SaveError::UnsupportedVersion(VERSION + 1))
+        );
+    }
+
+    // 古いバージョンのレコードを手で組み立てる
+    fn old_record(version: u8, values: &[i32]) -> [u8; RECORD_SIZE] {
+        let mut record = [0xFF; RECORD_SIZE];
+        let length = values.len() * 4;
+        record[..4].copy_from_slice(&[b'P', b'K', version, length as u8]);
+        for (index, value) in values.iter().enumerate() {
+            let position = HEADER_SIZE + index * 4;
+            record[position..position + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        let crc_position = HEADER_SIZE + length;
+        let crc = crc32(&record[..crc_position]);
+        record[crc_position..crc_position + CRC_SIZE].copy_from_slice(&crc.to_le_bytes());
+        record
+    }
+
+    #[test]
+    fn test_migrate_v1() {
+        let record = old_record(1, &[1234, 56, 7, 1230, -1, 1220]);
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                hunger: MAX_NEED,
+                happiness: MAX_NEED,
+                energy: MAX_NEED,
+                time: DateTime::default(),
+                day_start_step_count: 1234,
+                days: [0; DAYS_OF_HISTORY],
+                goals_achieved: 0,
+                profile: Profile::default(),
+                exercise: Exercise::new(),
+                theme: ThemeMode::Auto,
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v2() {
+        let record = old_record(2, &[1234, 56, 7, 1230, -1, 1220, 80, 60, 40]);
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                time: DateTime::default(),
+                day_start_step_count: 1234,
+                days: [0; DAYS_OF_HISTORY],
+                goals_achieved: 0,
+                profile: Profile::default(),
+                exercise: Exercise::new(),
+                theme: ThemeMode::Auto,
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v3() {
+        let time = save_data().time.seconds() as i32;
+        let record = old_record(3, &[1234, 56, 7, 1230, -1, 1220, 80, 60, 40, time, 1000]);
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                days: [0, 0, 0, 0, 0, 0, 234],
+                goals_achieved: 0,
+                profile: Profile::default(),
+                exercise: Exercise::new(),
+                theme: ThemeMode::Auto,
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v4() {
+        let time = save_data().time.seconds() as i32;
+        let record = old_record(
+            4,
+            &[1234, 56, 7, 1230, -1, 1220, 80, 60, 40, time, 1000, 100, 0, 300, 400, 500, 600, 234],
+        );
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                goals_achieved: 0,
+                profile: Profile::default(),
+                exercise: Exercise::new(),
+                theme: ThemeMode::Auto,
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v5() {
+        let time = save_data().time.seconds() as i32;
+        let record = old_record(
+            5,
+            &[1234, 56, 7, 1230, -1, 1220, 80, 60, 40, time, 1000, 100, 0, 300, 400, 500, 600, 234, 1],
+        );
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                profile: Profile::default(),
+                exercise: Exercise::new(),
+                theme: ThemeMode::Auto,
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v6() {
+        let time = save_data().time.seconds() as i32;
+        let record = old_record(
+            6,
+            &[
+                1234, 56, 7, 1230, -1, 1220, 80, 60, 40, time, 1000, 100, 0, 300, 400, 500, 600, 234, 1,
+                160, 50, 65, 16_380, 4_095,
+            ],
+        );
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                theme: ThemeMode::Auto,
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v7() {
+        let time = save_data().time.seconds() as i32;
+        let record = old_record(
+            7,
+            &[
+                1234, 56, 7, 1230, -1, 1220, 80, 60, 40, time, 1000, 100, 0, 300, 400, 500, 600, 234, 1,
+                160, 50, 65, 16_380, 4_095, 1,
+            ],
+        );
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                brightness: MAX_BRIGHTNESS,
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_v8() {
+        let time = save_data().time.seconds() as i32;
+        let record = old_record(
+            8,
+            &[
+                1234, 56, 7, 1230, -1, 1220, 80, 60, 40, time, 1000, 100, 0, 300, 400, 500, 600, 234, 1,
+                160, 50, 65, 16_380, 4_095, 1, 2,
+            ],
+        );
+
+        assert_eq!(
+            SaveData::decode::<Infallible>(&record),
+            Ok(SaveData {
+                detector: DetectorKind::default(),
+                goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+                ..save_data()
+            })
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut storage = MemoryStorage::new();
+
+        assert_eq!(SaveData::load(&mut storage), Err(SaveError::Empty));
+
+        save_data().save(&mut storage).unwrap();
+
+        assert_eq!(SaveData::load(&mut storage), Ok(save_data()));
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut game = Game::new();
+
+        save_data().restore(&mut game);
+
+        assert_eq!(game.pedometer.step_count, 1234);
+        assert_eq!(game.character.intimacy, 56);
+        assert_eq!(game.rice_ball.amount, 7);
+        assert_eq!(game.needs.hunger, 80);
+        assert_eq!(game.pedometer.history.days.totals()[DAYS_OF_HISTORY - 1], 234);
+        assert_eq!(game.goals.achieved, 1);
+        assert_eq!(game.goals.step_count(), 8000);
+        assert_eq!(game.pedometer.detector.kind(), DetectorKind::Threshold);
+        // 読み込んだ設定の色で描画する
+        assert!(game.dark);
+        assert_eq!(SaveData::from_game(&game), save_data());
+    }
+
+    #[test]
+    fn test_autosave() {
+        let mut autosave = Autosave::new(100);
+
+        assert!(!autosave.check(Event::Walked(99), 99));
+        assert!(autosave.check(Event::Walked(1), 100));
+        assert!(!autosave.check(Event::Walked(50), 150));
+        assert!(autosave.check(Event::Ate(1), 150));
+        assert!(!autosave.check(Event::Walked(99), 249));
+        assert!(autosave.check(Event::GameLost, 249));
+        assert!(autosave.check(Event::DayChanged, 250));
+        assert!(autosave.check(Event::GoalReached(Goals::default().goals[0]), 251));
+        assert!(!autosave.check(Event::RouteChanged(crate::router::Route::Home), 400));
+        assert!(autosave.check(Event::SettingsChanged, 400));
+        assert!(autosave.check(Event::HourPassed, 400));
+    }
+
+    #[test]
+    fn test_autosave_retry() {
+        let mut autosave = Autosave::new(100);
+
+        assert!(autosave.check(Event::Ate(1), 0));
+        assert_eq!(autosave.finish(Err(())), Err(()));
+
+        // 失敗したら、セーブしない出来事でもセーブし直す
+        assert!(autosave.check(Event::Walked(1), 1));
+        assert_eq!(autosave.finish(Ok::<(), ()>(())), Ok(()));
+        assert!(!autosave.check(Event::Walked(1), 2));
+    }
+}
@@ -0,0 +1,141 @@
+use heapless::{
+    consts::*,
+    Vec,
+};
+
+// 場面の中でアニメーションのコマを進める間隔(ミリ秒)
+pub const FRAME_INTERVAL_MS: u32 = 250;
+
+// 決まった時間だけ見せる演出の場面
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scene {
+    Eat,
+    Throw,
+    Success,
+    Fail,
+}
+
+// 場面と、その場面を見せる時間
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shot {
+    pub scene: Scene,
+    pub duration_ms: u32,
+}
+
+// 場面の変わり目
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    // 次の場面か次のコマに進んだ
+    Changed,
+    // 全ての場面を見せ終わった
+    Finished,
+}
+
+// 演出の場面を積んでおき、メインループの経過時間で一番上から順に見せていく。
+// delayで止めないので、演出の間も入力や歩数を受け付けられる
+pub struct SceneStack {
+    pub shots: Vec<Shot, U4>,
+    // 今の場面を見せ始めてからの時間
+    pub elapsed_ms: u32,
+}
+
+impl Default for SceneStack {
+    fn default() -> Self {
+        SceneStack::new()
+    }
+}
+
+impl SceneStack {
+    pub fn new() -> SceneStack {
+        SceneStack {
+            shots: Vec::new(),
+            elapsed_ms: 0,
+        }
+    }
+    // 場面を見せる順に受け取って、最初の場面が一番上になるように積み直す
+    pub fn play(&mut self, shots: &[Shot]) {
+        self.shots.clear();
+        self.elapsed_ms = 0;
+
+        for shot in shots.iter().rev() {
+            // 積みきれない場面は見せない
+            let _ = self.shots.push(*shot);
+        }
+    }
+    pub fn current(&self) -> Option<Scene> {
+        self.shots.last().map(|shot| shot.scene)
+    }
+    pub fn is_playing(&self) -> bool {
+        !self.shots.is_empty()
+    }
+    // 今の場面の何コマ目か
+    pub fn frame(&self) -> u32 {
+        self.elapsed_ms / FRAME_INTERVAL_MS
+    }
+    pub fn tick(&mut self, dt_ms: u32) -> Option<Transition> {
+        let duration_ms = self.shots.last()?.duration_ms;
+        let frame = self.frame();
+        self.elapsed_ms += dt_ms;
+
+        if self.elapsed_ms >= duration_ms {
+            return Some(self.skip());
+        }
+
+        if self.frame() != frame {
+            Some(Transition::Changed)
+        } else {
+            None
+        }
+    }
+    // 今の場面を終わらせて次の場面に進む
+    pub fn skip(&mut self) -> Transition {
+        self.shots.pop();
+        self.elapsed_ms = 0;
+
+        if self.is_playing() {
+            Transition::Changed
+        } else {
+            Transition::Finished
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shot(scene: Scene, duration_ms: u32) -> Shot {
+        Shot { scene, duration_ms }
+    }
+
+    #[test]
+    fn test_play() {
+        let mut scenes = SceneStack::new();
+
+        assert_eq!(scenes.tick(100), None);
+
+        scenes.play(&[shot(Scene::Throw, 500), shot(Scene::Success, 300)]);
+
+        assert_eq!(scenes.current(), Some(Scene::Throw));
+        assert_eq!(scenes.tick(200), None);
+        assert_eq!(scenes.tick(100), Some(Transition::Changed));
+        assert_eq!(scenes.frame(), 1);
+        assert_eq!(scenes.tick(200), Some(Transition::Changed));
+        assert_eq!(scenes.current(), Some(Scene::Success));
+        assert_eq!(scenes.frame(), 0);
+        assert_eq!(scenes.tick(300), Some(Transition::Finished));
+        assert!(!scenes.is_playing());
+    }
+
+    #[test]
+    fn test_skip() {
+        let mut scenes = SceneStack::new();
+        scenes.play(&[shot(Scene::Throw, 500), shot(Scene::Fail, 500)]);
+        scenes.tick(300);
+
+        assert_eq!(scenes.skip(), Transition::Changed);
+        assert_eq!(scenes.current(), Some(Scene::Fail));
+        assert_eq!(scenes.elapsed_ms, 0);
+        assert_eq!(scenes.skip(), Transition::Finished);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    clock::DateTime,
+    pedometer::DetectorKind,
+};
+
+// 画面の色の選び方
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    // 夜の間だけ暗い色にする
+    #[default]
+    Auto,
+}
+
+impl ThemeMode {
+    // セーブデータに書く番号
+    pub fn to_i32(self) -> i32 {
+        match self {
+            ThemeMode::Light => 0,
+            ThemeMode::Dark => 1,
+            ThemeMode::Auto => 2,
+        }
+    }
+    // 知らない番号なら初期値にする
+    pub fn from_i32(value: i32) -> ThemeMode {
+        match value {
+            0 => ThemeMode::Light,
+            1 => ThemeMode::Dark,
+            _ => ThemeMode::Auto,
+        }
+    }
+}
+
+// バックライトの明るさの段階。1が一番暗い
+pub const MIN_BRIGHTNESS: i32 = 1;
+pub const MAX_BRIGHTNESS: i32 = 4;
+
+// Statsで上下のボタンで変える設定
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SettingItem {
+    #[default]
+    Theme,
+    Brightness,
+    // 時計の時と分。時計はGameが合わせる
+    Hour,
+    Minute,
+    // 1日の目標歩数。目標はGameが持っている
+    Goal,
+    // 距離とカロリーの計算に使う体格。体格はGameが持っている
+    Height,
+    Weight,
+    Stride,
+    Detector,
+}
+
+// 押すたびにこの順で選ぶ
+const SETTING_ITEMS: [SettingItem; 9] = [
+    SettingItem::Theme,
+    SettingItem::Brightness,
+    SettingItem::Hour,
+    SettingItem::Minute,
+    SettingItem::Goal,
+    SettingItem::Height,
+    SettingItem::Weight,
+    SettingItem::Stride,
+    SettingItem::Detector,
+];
+
+// プレイヤーが選べる設定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    pub theme: ThemeMode,
+    pub brightness: i32,
+    // 歩数の検出アルゴリズム
+    pub detector: DetectorKind,
+    // 選んでいる設定。セーブはしない
+    pub selected: SettingItem,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            theme: ThemeMode::default(),
+            brightness: MAX_BRIGHTNESS,
+            detector: DetectorKind::default(),
+            selected: SettingItem::default(),
+        }
+    }
+    // 変える設定を順に切り替える
+    pub fn select_next(&mut self) {
+        let index = SETTING_ITEMS.iter().position(|item| *item == self.selected).unwrap_or(0);
+        self.selected = SETTING_ITEMS[(index + 1) % SETTING_ITEMS.len()];
+    }
+    // 選んでいる設定を次の値にする
+    pub fn increase(&mut self) {
+        match self.selected {
+            SettingItem::Theme => self.next_theme(),
+            SettingItem::Brightness => self.brightness = (self.brightness + 1).min(MAX_BRIGHTNESS),
+            SettingItem::Detector => self.next_detector(),
+            // Settingsの外にあるものはGameが変える
+            _ => {}
+        }
+    }
+    pub fn decrease(&mut self) {
+        match self.selected {
+            SettingItem::Theme => self.previous_theme(),
+            SettingItem::Brightness => self.brightness = (self.brightness - 1).max(MIN_BRIGHTNESS),
+            SettingItem::Detector => self.next_detector(),
+            // Settingsの外にあるものはGameが変える
+            _ => {}
+        }
+    }
+    // Light、Dark、Autoの順に切り替える
+    pub fn next_theme(&mut self) {
+        self.theme = match self.theme {
+            ThemeMode::Light => ThemeMode::Dark,
+            ThemeMode::Dark => ThemeMode::Auto,
+            ThemeMode::Auto => ThemeMode::Light,
+        };
+    }
+    pub fn previous_theme(&mut self) {
+        self.theme = match self.theme {
+            ThemeMode::Light => ThemeMode::Auto,
+            ThemeMode::Dark => ThemeMode::Light,
+            ThemeMode::Auto => ThemeMode::Dark,
+        };
+    }
+    // 2つしかないので、上下どちらのボタンでも切り替える
+    pub fn next_detector(&mut self) {
+        self.detector = match self.detector {
+            DetectorKind::Threshold => DetectorKind::Peak,
+            DetectorKind::Peak => DetectorKind::Threshold,
+        };
+    }
+    // この時刻に暗い色で描画するか
+    pub fn is_dark(&self, time: &DateTime) -> bool {
+        match self.theme {
+            ThemeMode::Light => false,
+            ThemeMode::Dark => true,
+            ThemeMode::Auto => time.is_night(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_theme() {
+        let mut settings = Settings::new();
+
+        assert_eq!(settings.theme, ThemeMode::Auto);
+
+        settings.next_theme();
+        assert_eq!(settings.theme, ThemeMode::Light);
+        settings.next_theme();
+        assert_eq!(settings.theme, ThemeMode::Dark);
+        settings.previous_theme();
+        assert_eq!(settings.theme, ThemeMode::Light);
+        settings.previous_theme();
+        assert_eq!(settings.theme, ThemeMode::Auto);
+    }
+
+    #[test]
+    fn test_change_brightness() {
+        let mut settings = Settings::new();
+
+        // 明るさを選ぶまでは色の設定を変える
+        settings.decrease();
+        assert_eq!(settings.theme, ThemeMode::Dark);
+        assert_eq!(settings.brightness, MAX_BRIGHTNESS);
+
+        settings.select_next();
+        assert_eq!(settings.selected, SettingItem::Brightness);
+
+        settings.increase();
+        assert_eq!(settings.brightness, MAX_BRIGHTNESS);
+        for _ in 0..MAX_BRIGHTNESS {
+            settings.decrease();
+        }
+        assert_eq!(settings.brightness, MIN_BRIGHTNESS);
+        assert_eq!(settings.theme, ThemeMode::Dark);
+
+        // 時計と目標と体格と歩数の数え方を選んで一周すると色の設定に戻る
+        for item in [
+            SettingItem::Hour,
+            SettingItem::Minute,
+            SettingItem::Goal,
+            SettingItem::Height,
+            SettingItem::Weight,
+            SettingItem::Stride,
+            SettingItem::Detector,
+            SettingItem::Theme,
+        ] {
+            settings.select_next();
+            assert_eq!(settings.selected, item);
+        }
+    }
+
+    #[test]
+    fn test_switch_detector() {
+        let mut settings = Settings::new();
+        settings.selected = SettingItem::Detector;
+
+        settings.increase();
+        assert_eq!(settings.detector, DetectorKind::Threshold);
+        settings.decrease();
+        assert_eq!(settings.detector, DetectorKind::Peak);
+    }
+
+    #[test]
+    fn test_is_dark() {
+        let day = DateTime { hour: 12, ..DateTime::default() };
+        let night = DateTime { hour: 23, ..DateTime::default() };
+        let mut settings = Settings::new();
+
+        assert!(!settings.is_dark(&day));
+        assert!(settings.is_dark(&night));
+
+        settings.theme = ThemeMode::Light;
+        assert!(!settings.is_dark(&night));
+
+        settings.theme = ThemeMode::Dark;
+        assert!(settings.is_dark(&day));
+    }
+
+    #[test]
+    fn test_theme_number() {
+        for theme in [ThemeMode::Light, ThemeMode::Dark, ThemeMode::Auto] {
+            assert_eq!(ThemeMode::from_i32(theme.to_i32()), theme);
+        }
+        assert_eq!(ThemeMode::from_i32(-1), ThemeMode::Auto);
+    }
+}
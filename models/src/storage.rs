@@ -0,0 +1,43 @@
+use crate::save::RECORD_SIZE;
+
+// セーブデータのレコードを読み書きする先
+pub trait Storage {
+    type Error;
+
+    // 保存されているレコードを読み込む。何も保存されていないときは0xFFで埋める
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), Self::Error>;
+    // レコードを書き込む
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), Self::Error>;
+}
+
+// ホストでのテスト用に、消去済みのフラッシュと同じく0xFFで初期化されたメモリに保存する
+pub struct MemoryStorage {
+    pub record: [u8; RECORD_SIZE],
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        MemoryStorage::new()
+    }
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage {
+            record: [0xFF; RECORD_SIZE],
+        }
+    }
+}
+
+impl Storage for MemoryStorage {
+    type Error = core::convert::Infallible;
+
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), Self::Error> {
+        record.copy_from_slice(&self.record);
+        Ok(())
+    }
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), Self::Error> {
+        self.record.copy_from_slice(record);
+        Ok(())
+    }
+}
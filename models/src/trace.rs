@@ -0,0 +1,216 @@
+use accelerometer::vector::F32x3;
+use core::fmt::{self, Write};
+
+use crate::pedometer::{
+    Pedometer,
+    Sample,
+};
+
+// 歩数計を再生して試すための加速度のトレース。
+// 1行に1サンプルを「経過時間(ミリ秒),x,y,z」のCSVで書く。#で始まる行はコメントで、
+// 「# label: walking」「# steps: 36」のように名前と正解の歩数を書いておく
+pub const HEADER: &str = "timestamp_ms,x,y,z";
+
+impl Sample {
+    pub fn parse(line: &str) -> Option<Sample> {
+        let mut fields = line.trim().split(',');
+        let timestamp_ms = fields.next()?.trim().parse().ok()?;
+        let x = fields.next()?.trim().parse().ok()?;
+        let y = fields.next()?.trim().parse().ok()?;
+        let z = fields.next()?.trim().parse().ok()?;
+
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Some(Sample {
+            timestamp_ms,
+            accel: F32x3::new(x, y, z),
+        })
+    }
+    // 実機でシリアルに書き出して記録できるように、同じ形式の1行を書く
+    pub fn write<W: Write>(&self, writer: &mut W) -> fmt::Result {
+        let F32x3 { x, y, z } = self.accel;
+        writeln!(writer, "{},{:.3},{:.3},{:.3}", self.timestamp_ms, x, y, z)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TraceError {
+    // 読めなかった行の番号(1始まり)
+    InvalidLine(usize),
+}
+
+pub struct Trace<'a> {
+    pub text: &'a str,
+}
+
+impl<'a> Trace<'a> {
+    pub fn new(text: &'a str) -> Trace<'a> {
+        Trace { text }
+    }
+    // 「# label: walking」のようなコメントの値
+    fn comment(&self, key: &str) -> Option<&'a str> {
+        self.text
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('#'))
+            .filter_map(|comment| comment.split_once(':'))
+            .find(|(name, _)| name.trim() == key)
+            .map(|(_, value)| value.trim())
+    }
+    pub fn label(&self) -> Option<&'a str> {
+        self.comment("label")
+    }
+    // 正解の歩数
+    pub fn steps(&self) -> Option<i32> {
+        self.comment("steps")?.parse().ok()
+    }
+    pub fn samples(&self) -> impl Iterator<Item = Result<Sample, TraceError>> + 'a {
+        self.text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#') && line != HEADER
+            })
+            .map(|(index, line)| Sample::parse(line).ok_or(TraceError::InvalidLine(index + 1)))
+    }
+    // サンプルを順に歩数計に渡して、数えた歩数を返す
+    pub fn replay(&self, pedometer: &mut Pedometer) -> Result<i32, TraceError> {
+        let start_step_count = pedometer.step_count;
+        // トレースごとに時刻は0から数え直す
+        pedometer.last_timestamp_ms = None;
+
+        for sample in self.samples() {
+            pedometer.push(sample?);
+        }
+
+        Ok(pedometer.step_count - start_step_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::Game,
+        pedometer::{
+            Detector,
+            PeakDetector,
+            ThresholdDetector,
+        },
+    };
+
+    // traces/synthetic/generate.pyで合成したトレース。実機で記録したものではないので、
+    // ここで確かめられるのは合成した歩き方や揺れを数えられるかどうかまで
+    const SYNTHETIC_TRACES: [&str; 5] = [
+        include_str!("../traces/synthetic/walking.csv"),
+        include_str!("../traces/synthetic/slow_walking.csv"),
+        include_str!("../traces/synthetic/running.csv"),
+        include_str!("../traces/synthetic/sitting.csv"),
+        include_str!("../traces/synthetic/driving.csv"),
+    ];
+
+    #[test]
+    fn test_parse_sample() {
+        assert_eq!(
+            Sample::parse(" 20,0.250,-0.930, 1.5 "),
+            Some(Sample {
+                timestamp_ms: 20,
+                accel: F32x3::new(0.25, -0.93, 1.5),
+            })
+        );
+        assert_eq!(Sample::parse("20,0.250,-0.930"), None);
+        assert_eq!(Sample::parse("20,0.250,-0.930,1.5,0"), None);
+        assert_eq!(Sample::parse("x,0.250,-0.930,1.5"), None);
+    }
+
+    #[test]
+    fn test_write_sample() {
+        let sample = Sample {
+            timestamp_ms: 20,
+            accel: F32x3::new(0.25, -0.93, 1.5),
+        };
+        let mut line = String::new();
+        sample.write(&mut line).unwrap();
+
+        assert_eq!(line, "20,0.250,-0.930,1.500\n");
+        assert_eq!(Sample::parse(&line), Some(sample));
+    }
+
+    #[test]
+    fn test_trace() {
+        let trace = Trace::new("# label: test\n# steps: 1\ntimestamp_ms,x,y,z\n0,0,0,1\n\n100,0,0,2\n400,0,0,1\n");
+
+        assert_eq!(trace.label(), Some("test"));
+        assert_eq!(trace.steps(), Some(1));
+        assert_eq!(trace.samples().count(), 3);
+
+        // 1歩だけなので、歩き始めを確かめずに数える
+        let mut pedometer = Pedometer::with_detector(Detector::Peak(PeakDetector {
+            min_regular_steps: 1,
+            ..PeakDetector::new()
+        }));
+
+        assert_eq!(trace.replay(&mut pedometer), Ok(1));
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let trace = Trace::new("# label: test\n0,0,0,1\n100,0,0\n");
+
+        assert_eq!(trace.replay(&mut Pedometer::new()), Err(TraceError::InvalidLine(3)));
+    }
+
+    // (トレース, PeakDetectorで許す誤差)
+    // 止まっているときや車の段差の揺れは1歩も数えないこと
+    const PEAK_TOLERANCES: [(&str, i32); 5] = [
+        (SYNTHETIC_TRACES[0], 2),
+        (SYNTHETIC_TRACES[1], 2),
+        (SYNTHETIC_TRACES[2], 3),
+        (SYNTHETIC_TRACES[3], 0),
+        (SYNTHETIC_TRACES[4], 0),
+    ];
+
+    fn assert_replay(text: &str, pedometer: &mut Pedometer, tolerance: i32) {
+        let trace = Trace::new(text);
+        let steps = trace.replay(pedometer).unwrap();
+        let expected = trace.steps().unwrap();
+
+        assert!(
+            (steps - expected).abs() <= tolerance,
+            "{}: expected {} but counted {}",
+            trace.label().unwrap(),
+            expected,
+            steps
+        );
+    }
+
+    #[test]
+    fn test_replay_synthetic_peak_detector() {
+        for (text, tolerance) in PEAK_TOLERANCES.iter() {
+            assert_replay(text, &mut Pedometer::with_detector(Detector::Peak(PeakDetector::new())), *tolerance);
+        }
+    }
+
+    #[test]
+    fn test_replay_synthetic_game_detector() {
+        // 実機は設定を変えるまでGameの初期の検出アルゴリズムで数えるので、歩きも取りこぼさないこと
+        for (text, tolerance) in PEAK_TOLERANCES.iter() {
+            assert_replay(text, &mut Game::new().pedometer, *tolerance);
+        }
+    }
+
+    #[test]
+    fn test_replay_synthetic_threshold_detector() {
+        // 従来の閾値方式は強い揺れの走りは数えられるが、歩きはほとんど取りこぼす
+        let running = Trace::new(SYNTHETIC_TRACES[2]);
+        let sitting = Trace::new(SYNTHETIC_TRACES[3]);
+        let walking = Trace::new(SYNTHETIC_TRACES[0]);
+        let threshold = || Pedometer::with_detector(Detector::Threshold(ThresholdDetector::new()));
+
+        assert_eq!(running.replay(&mut threshold()), Ok(running.steps().unwrap()));
+        assert_eq!(sitting.replay(&mut threshold()), Ok(0));
+        assert!(walking.replay(&mut threshold()).unwrap() < walking.steps().unwrap() / 2);
+    }
+}
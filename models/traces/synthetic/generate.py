@@ -0,0 +1,100 @@
+#!/usr/bin/env python3
+# 歩数計の再生テストに使う加速度のトレースを合成する。
+# 実機で記録したトレースは、これと混ぜずに別のディレクトリに置く。
+#
+#   python3 models/traces/synthetic/generate.py
+#
+# 乱数の種を固定しているので、何度実行しても同じファイルができる。
+
+import math
+import os
+import random
+
+RATE_HZ = 50
+DURATION_S = 20
+# ポケットに入れたときのように、重力が少し傾いた向きにかかる
+GRAVITY = (0.25, -0.93, 0.27)
+
+
+def write(name, label, steps, samples):
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), name + ".csv")
+    with open(path, "w") as file:
+        file.write("# label: {}\n".format(label))
+        file.write("# steps: {}\n".format(steps))
+        file.write("# synthesized by generate.py at {}Hz\n".format(RATE_HZ))
+        file.write("timestamp_ms,x,y,z\n")
+        for index, (x, y, z) in enumerate(samples):
+            file.write("{},{:.3f},{:.3f},{:.3f}\n".format(index * 1000 // RATE_HZ, x, y, z))
+
+
+def along_gravity(magnitude, noise, rng):
+    # 重力の向きに揺れを足し、各軸に雑音を足す
+    return tuple(axis * magnitude + rng.gauss(0, noise) for axis in GRAVITY)
+
+
+def gait(rng, frequency, amplitude, noise):
+    # 1歩ごとに踏み込みの山と、着地の小さな谷がある歩き方
+    samples = []
+    period = 1.0 / frequency
+    starts = [rng.random() * 0.2]
+    for index in range(RATE_HZ * DURATION_S):
+        t = index / RATE_HZ
+        if t >= starts[-1] + period:
+            starts.append(starts[-1] + period * rng.uniform(0.93, 1.07))
+        local = (t - starts[-1]) / period
+        if local < 0.35:
+            bump = amplitude * math.sin(math.pi * local / 0.35)
+        elif 0.5 <= local < 0.65:
+            bump = -0.3 * amplitude * math.sin(math.pi * (local - 0.5) / 0.15)
+        else:
+            bump = 0.0
+        samples.append(along_gravity(1.0 + bump, noise, rng))
+    # 最後まで山を描き切った歩数を数える
+    steps = sum(1 for start in starts if start + period * 0.35 <= DURATION_S)
+    return steps, samples
+
+
+def sitting(rng):
+    samples = []
+    for index in range(RATE_HZ * DURATION_S):
+        t = index / RATE_HZ
+        # ゆっくり姿勢を変える
+        sway = 0.03 * math.sin(2 * math.pi * 0.1 * t)
+        samples.append(along_gravity(1.0 + sway, 0.01, rng))
+    return samples
+
+
+def driving(rng):
+    samples = []
+    pothole = 0.0
+    for index in range(RATE_HZ * DURATION_S):
+        t = index / RATE_HZ
+        # エンジンと路面の細かい振動、ゆっくりした車体の揺れ、ときどき段差
+        vibration = 0.12 * math.sin(2 * math.pi * 11 * t) + 0.08 * math.sin(2 * math.pi * 17 * t + 1)
+        sway = 0.05 * math.sin(2 * math.pi * 0.4 * t)
+        if rng.random() < 0.01:
+            pothole = 0.35
+        bump = pothole
+        pothole *= 0.5
+        samples.append(along_gravity(1.0 + vibration + sway + bump, 0.03, rng))
+    return samples
+
+
+def main():
+    rng = random.Random(20210101)
+
+    steps, samples = gait(rng, 1.8, 0.35, 0.03)
+    write("walking", "walking", steps, samples)
+
+    steps, samples = gait(rng, 1.0, 0.15, 0.02)
+    write("slow_walking", "slow_walking", steps, samples)
+
+    steps, samples = gait(rng, 2.8, 1.0, 0.05)
+    write("running", "running", steps, samples)
+
+    write("sitting", "sitting", 0, sitting(rng))
+    write("driving", "driving", 0, driving(rng))
+
+
+if __name__ == "__main__":
+    main()
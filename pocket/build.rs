@@ -0,0 +1,15 @@
+// セーブデータの領域を除いたmemory.xを、wio_terminalのものより先に見つかるように置く
+use std::{
+    env,
+    fs,
+    path::PathBuf,
+};
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::copy("memory.x", out_dir.join("memory.x")).unwrap();
+
+    println!("cargo:rustc-link-search={}", out_dir.display());
+    println!("cargo:rerun-if-changed=memory.x");
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,8 @@
+MEMORY
+{
+  /* Wio Terminalのブートローダーのために先頭の16KBを空けておく。
+     末尾の32KBはセーブデータに使うので、プログラムが大きくなりすぎたらリンクで失敗させる */
+  FLASH (rx)  : ORIGIN = 0x00000000 + 16K, LENGTH = 512K - 16K - 32K
+  RAM   (rxw) : ORIGIN = 0x20000000, LENGTH = 192K
+}
+_stack_start = ORIGIN(RAM) + LENGTH(RAM);
@@ -0,0 +1,32 @@
+use views::{
+    clock_view::ClockView,
+    helpers::theme,
+};
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+use models::game::{
+    Game,
+    Outcome,
+};
+
+pub struct ClockViewController;
+
+impl ClockViewController {
+    #[allow(unused_must_use)]
+    pub fn watch<T>(
+        display: &mut T,
+        game: &Game,
+        outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        // 機嫌が変わるのは夜になったときもあるので、時計も描き直す
+        if let Outcome::MinutePassed | Outcome::DayChanged | Outcome::MoodChanged = outcome {
+            ClockView::render(display, theme::get_theme(game.dark), &game.time);
+        }
+    }
+}
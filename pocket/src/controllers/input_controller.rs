@@ -0,0 +1,49 @@
+use models::input::InputEvent;
+
+use heapless::{consts::*, Vec};
+use wio_terminal::{
+    prelude::*,
+    hal::gpio::{
+        *,
+        v2::pin::{
+            PD08,
+            PD09,
+            PD10,
+            PD12,
+            PD20,
+        },
+    },
+};
+
+pub struct InputController {
+    pub switch_x: Pin<PD08, Input<Floating>>,
+    pub switch_y: Pin<PD09, Input<Floating>>,
+    pub switch_z: Pin<PD10, Input<Floating>>,
+    pub switch_b: Pin<PD12, Input<Floating>>,
+    pub switch_u: Pin<PD20, Input<Floating>>,
+}
+
+impl InputController {
+    // 押されているボタンを入力イベントに変換する
+    pub fn watch(&self) -> Vec<InputEvent, U5> {
+        let mut events = Vec::new();
+
+        if self.switch_y.is_low().unwrap() {
+            events.push(InputEvent::Right).ok();
+        }
+        if self.switch_b.is_low().unwrap() {
+            events.push(InputEvent::Left).ok();
+        }
+        if self.switch_x.is_low().unwrap() {
+            events.push(InputEvent::Down).ok();
+        }
+        if self.switch_u.is_low().unwrap() {
+            events.push(InputEvent::Up).ok();
+        }
+        if self.switch_z.is_low().unwrap() {
+            events.push(InputEvent::Press).ok();
+        }
+
+        events
+    }
+}
@@ -1,116 +1,127 @@
-use crate::models::{
-    bet::Bet,
-    character::Character,
-    meal::Meal,
-    navigation::Navigation,
-    rice_ball::RiceBall,
-    router::{
-        Route,
-        Router,
-    },
-    shuriken::Shuriken,
-    target::Target,
-};
 use crate::controllers::pages::{
+    game_page_controller::GamePageController,
     home_page_controller::HomePageController,
     meal_page_controller::MealPageController,
     play_page_controller::PlayPageController,
+    scene_page_controller::ScenePageController,
+    stats_page_controller::StatsPageController,
 };
 
 use embedded_graphics::{
     pixelcolor::Rgb565,
     prelude::*,
 };
-use wio_terminal::hal::{
-    delay::*,
-    pwm::*,
-    gpio::{
-        *,
-        v2::pin::{
-            PD08,
-            PD20,
-            PD10,
-        }
+use models::{
+    game::{
+        Game,
+        Outcome,
     },
+    router::Route,
+    target::TargetPosition,
+};
+use views::helpers::{
+    compositor::{
+        Compositor,
+        FlushTarget,
+    },
+    dirty::DirtyTracker,
+    screen,
+    theme,
 };
-
-use super::pages::game_page_controller::GamePageController;
-
 pub struct PageController;
 
 impl PageController {
-    #![allow(clippy::too_many_arguments)]
+    #[allow(unused_must_use)]
     pub fn watch<T>(
         display: &mut T,
-        buzzer: &mut Tcc0Pwm,
-        delay: &mut Delay,
-        switch_x: &Pin<PD08, Input<Floating>>,
-        switch_u: &Pin<PD20, Input<Floating>>,
-        switch_z: &Pin<PD10, Input<Floating>>,
-        navigation: &mut Navigation,
-        router: &mut Router,
-        character: &mut Character,
-        bet: &mut Bet,
-        meal: &mut Meal,
-        rice_ball: &mut RiceBall,
-        shuriken: &mut Shuriken,
-        target: &mut Target,
+        compositor: &mut Compositor,
+        targets: &mut DirtyTracker<TargetPosition>,
+        game: &Game,
+        outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565> + FlushTarget,
+    {
+        // ページ全体を描き直すときは、RAMの上で帯ごとに描いてからまとめて送る
+        if redraws_page(game, outcome) {
+            let background = theme::get_theme(game.dark).background;
+            compositor.compose(display, &screen::PAGE_AREA, background, |tile| {
+                PageController::route(tile, targets, game, outcome);
+            });
+        } else {
+            PageController::route(display, targets, game, outcome);
+        }
+    }
+    fn route<T>(
+        display: &mut T,
+        targets: &mut DirtyTracker<TargetPosition>,
+        game: &Game,
+        outcome: Outcome,
     )
     where
         T: DrawTarget<Rgb565>,
     {
-        match router.route {
-            Route::Home => {
-                HomePageController::watch(
-                    buzzer,
-                    delay,
-                    switch_z,
-                    navigation
-                );
+        match outcome {
+            Outcome::Routed(Route::Home) | Outcome::MoodChanged => {
+                HomePageController::watch(display, game, outcome);
+            },
+            Outcome::Routed(Route::Meal) | Outcome::MealChanged => {
+                MealPageController::watch(display, game, outcome);
+            },
+            Outcome::Routed(Route::Play) | Outcome::BetChanged => {
+                PlayPageController::watch(display, game, outcome);
+            },
+            Outcome::Routed(Route::Game) | Outcome::TargetMoved => {
+                GamePageController::watch(display, targets, game, outcome);
+            },
+            // 食事やゲームの結果を演出する
+            Outcome::Ate
+            | Outcome::GameWon
+            | Outcome::GameLost
+            | Outcome::SceneChanged
+            | Outcome::SceneEnded => {
+                ScenePageController::watch(display, game, outcome);
             },
-            Route::Meal => {
-                MealPageController::watch(
-                    display,
-                    buzzer,
-                    delay,
-                    switch_x,
-                    switch_u,
-                    switch_z,
-                    navigation,
-                    router,
-                    character,
-                    meal,
-                    rice_ball
-                );
+            Outcome::Routed(Route::Stats)
+            | Outcome::SettingsChanged
+            | Outcome::GoalChanged
+            | Outcome::Walked
+            | Outcome::GoalReached
+            | Outcome::DayChanged => {
+                StatsPageController::watch(display, game, outcome);
             },
-            Route::Play => {
-                PlayPageController::watch(
-                    display,
-                    buzzer,
-                    delay,
-                    switch_x,
-                    switch_u,
-                    switch_z,
-                    navigation,
-                    router,
-                    bet,
-                    shuriken
-                );
+            // 歩き始めたらHomeのキャラクターも喜ぶ
+            Outcome::ActivityChanged => {
+                HomePageController::watch(display, game, outcome);
+                StatsPageController::watch(display, game, outcome);
             },
-            Route::Game => {
-                GamePageController::watch(
-                    display,
-                    buzzer,
-                    delay,
-                    switch_z,
-                    navigation,
-                    router,
-                    character,
-                    bet,
-                    shuriken,
-                    target
-                )
-            }
+            // 色が変わったり時計を合わせたりしたら今のページを開き直したように描く
+            Outcome::ThemeChanged | Outcome::TimeSet => {
+                PageController::route(display, targets, game, Outcome::Routed(game.router.route));
+            },
+            Outcome::Focused(_) | Outcome::Touched | Outcome::MinutePassed => {}
+        }
+    }
+}
+
+// ページ全体を描き直す出来事か。
+// 帯ごとに描くたびに呼ぶので、描き直す部分を覚えている描画は毎回全体を描くものに限る
+fn redraws_page(game: &Game, outcome: Outcome) -> bool {
+    match outcome {
+        Outcome::Routed(_)
+        | Outcome::MoodChanged
+        | Outcome::SceneEnded
+        | Outcome::SettingsChanged
+        | Outcome::GoalChanged
+        | Outcome::ThemeChanged
+        | Outcome::TimeSet => true,
+        // 演出の最初のコマはページ全体を描く
+        Outcome::Ate | Outcome::GameWon | Outcome::GameLost | Outcome::SceneChanged => {
+            game.scenes.frame() == 0
+        }
+        Outcome::Walked | Outcome::GoalReached | Outcome::DayChanged => {
+            game.router.route == Route::Stats
         }
+        _ => false,
     }
-}
\ No newline at end of file
+}
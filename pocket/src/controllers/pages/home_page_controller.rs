@@ -1,32 +1,65 @@
-use crate::helpers::buzzer::beep;
-use crate::models::{
-    navigation::Navigation,
-    router::Route,
+use views::{
+    helpers::{
+        image,
+        theme,
+        sprite::AnimatedSprite,
+    },
+    pages::home_page::HomePage,
 };
 
-use wio_terminal::{
+use embedded_graphics::{
+    pixelcolor::Rgb565,
     prelude::*,
-    hal::{
-        delay::*,
-        pwm::*,
-        gpio::{
-            *,
-            v2::pin::PD10,
-        },
+};
+use models::{
+    game::{
+        Game,
+        Outcome,
     },
+    router::Route,
 };
 
 pub struct HomePageController;
 
 impl HomePageController {
-    pub fn watch(
-        buzzer: &mut Tcc0Pwm,
-        delay: &mut Delay,
-        switch_z: &Pin<PD10, Input<Floating>>,
-        navigation: &Navigation,
-    ) {
-        if switch_z.is_low().unwrap() && navigation.focus == Route::Home {
-            beep(buzzer, delay, 800.hz(), 200u16);
+    #[allow(unused_must_use)]
+    pub fn watch<T>(
+        display: &mut T,
+        game: &Game,
+        outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let theme = theme::get_theme(game.dark);
+        if outcome == Outcome::Routed(Route::Home) || outcome == Outcome::MoodChanged {
+            HomePage::render(display, theme, &game.mood());
+        }
+        if outcome == Outcome::ActivityChanged && game.is_home_visible() {
+            HomePage::render(display, theme, &game.mood());
+        }
+    }
+    // Homeが見えている間は機嫌に合わせた待機中のアニメーションを進める
+    #[allow(unused_must_use)]
+    pub fn animate<T>(
+        display: &mut T,
+        sprite: &mut AnimatedSprite,
+        game: &Game,
+        dt_ms: u32,
+    )
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        // 他のページを見ている間は、Homeに戻って描画される最初のコマに合わせておく
+        if !game.is_home_visible() {
+            sprite.reset();
+            return;
+        }
+
+        let state = image::get_mood_state(&game.mood());
+        sprite.play(image::get_character_frames(&state));
+        if sprite.tick(dt_ms) {
+            HomePage::render_sprite(display, theme::get_theme(game.dark), &game.mood(), sprite);
         }
     }
-}
\ No newline at end of file
+}
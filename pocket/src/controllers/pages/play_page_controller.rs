@@ -1,14 +1,5 @@
-use crate::helpers::buzzer::beep;
-use crate::models::{
-    bet::Bet,
-    navigation::Navigation,
-    router::{
-        Route,
-        Router,
-    },
-    shuriken::Shuriken
-};
-use crate::views::{
+use views::{
+    helpers::theme,
     pages::play_page::PlayPage,
 };
 
@@ -16,58 +7,23 @@ use embedded_graphics::{
     pixelcolor::Rgb565,
     prelude::*,
 };
-use wio_terminal::{
-    prelude::*,
-    hal::{
-        delay::*,
-        pwm::*,
-        gpio::{
-            *,
-            v2::pin::{
-                PD08,
-                PD20,
-                PD10,
-            },
-        },
-    },
+use models::game::{
+    Game,
+    Outcome,
 };
 
 pub struct PlayPageController;
 
 impl PlayPageController {
-    #![allow(clippy::too_many_arguments)]
     #[allow(unused_must_use)]
     pub fn watch<T>(
         display: &mut T,
-        buzzer: &mut Tcc0Pwm,
-        delay: &mut Delay,
-        switch_x: &Pin<PD08, Input<Floating>>,
-        switch_u: &Pin<PD20, Input<Floating>>,
-        switch_z: &Pin<PD10, Input<Floating>>,
-        navigation: &mut Navigation,
-        router: &mut Router,
-        bet: &mut Bet,
-        shuriken: &mut Shuriken,
+        game: &Game,
+        _outcome: Outcome,
     )
     where
         T: DrawTarget<Rgb565>,
     {
-        if switch_x.is_low().unwrap() {
-            beep(buzzer, delay, 800.hz(), 200u16);
-            // 掛け金の量を減らす
-            Bet::decrease(bet);
-            PlayPage::render(display, &bet, &shuriken);
-        }
-        if switch_u.is_low().unwrap() {
-            beep(buzzer, delay, 800.hz(), 200u16);
-            // 掛け金の量を増やす
-            Bet::increase(bet, shuriken.amount);
-            PlayPage::render(display, &bet, &shuriken);
-        }
-        if switch_z.is_low().unwrap() && navigation.focus == Route::Play && bet.amount > 0 {
-            beep(buzzer, delay, 800.hz(), 200u16);
-            // ルーティングをGameにアップデート
-            Router::update(router, Route::Game);
-        }
+        PlayPage::render(display, theme::get_theme(game.dark), &game.bet, &game.shuriken);
     }
-}
\ No newline at end of file
+}
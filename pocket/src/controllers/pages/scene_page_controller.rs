@@ -0,0 +1,62 @@
+use views::{
+    helpers::theme,
+    pages::{
+        eat_page::EatPage,
+        fail_page::FailPage,
+        home_page::HomePage,
+        success_page::SuccessPage,
+        throw_page::ThrowPage,
+    },
+};
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+use models::{
+    game::{
+        Game,
+        Outcome,
+    },
+    scene::Scene,
+};
+
+pub struct ScenePageController;
+
+impl ScenePageController {
+    #[allow(unused_must_use)]
+    pub fn watch<T>(
+        display: &mut T,
+        game: &Game,
+        outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let theme = theme::get_theme(game.dark);
+        // 演出が終わったらHomeに戻る
+        if outcome == Outcome::SceneEnded {
+            HomePage::render(display, theme, &game.mood());
+            return;
+        }
+
+        let frame = game.scenes.frame();
+        match game.scenes.current() {
+            // 手裏剣はコマごとに動かす
+            Some(Scene::Throw) => {
+                ThrowPage::render(display, theme, frame);
+            }
+            // それ以外の場面は最初のコマだけ描画する
+            Some(Scene::Eat) if frame == 0 => {
+                EatPage::render(display, theme);
+            }
+            Some(Scene::Success) if frame == 0 => {
+                SuccessPage::render(display, theme);
+            }
+            Some(Scene::Fail) if frame == 0 => {
+                FailPage::render(display, theme);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use views::{
+    helpers::theme,
+    pages::stats_page::{
+        StatsModel,
+        StatsPage,
+    },
+};
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+use models::{
+    game::{
+        Game,
+        Outcome,
+    },
+    router::Route,
+};
+
+pub struct StatsPageController;
+
+impl StatsPageController {
+    #[allow(unused_must_use)]
+    pub fn watch<T>(
+        display: &mut T,
+        game: &Game,
+        _outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        // 開いている間に歩いたり日付や今していることや設定が変わったりしたら描き直す
+        if game.router.route == Route::Stats {
+            StatsPage::render(
+                display,
+                theme::get_theme(game.dark),
+                &StatsModel::from_game(game),
+            );
+        }
+    }
+}
@@ -1,16 +1,19 @@
-use crate::models::{
-    character::Character,
-    pedometer::Pedometer,
-    rice_ball::RiceBall,
-    shuriken::Shuriken,
+use views::{
+    helpers::{
+        dirty::DirtyTracker,
+        theme,
+    },
+    pedometer_view::PedometerView,
 };
-use crate::views::pedometer_view::PedometerView;
 
 use embedded_graphics::{
     pixelcolor::Rgb565,
     prelude::*,
 };
-use accelerometer::vector::F32x3;
+use models::game::{
+    Game,
+    Outcome,
+};
 
 pub struct PedometerViewController;
 
@@ -18,16 +21,16 @@ impl PedometerViewController {
     #[allow(unused_must_use)]
     pub fn watch<T>(
         display: &mut T,
-        normalized_accel: F32x3,
-        pedometer: &mut Pedometer,
-        rice_ball: &mut RiceBall,
-        shuriken: &mut Shuriken,
+        tracker: &mut DirtyTracker<i32>,
+        game: &Game,
+        outcome: Outcome,
     )
     where
         T: DrawTarget<Rgb565>,
     {
-        Pedometer::update(pedometer, normalized_accel);
-        Character::walk(pedometer, rice_ball, shuriken);
-        PedometerView::render(display, &mut pedometer.step_count);
+        // 日付が変わったら今日の歩数を0から表示し直す。歩数が変わっていなければ描かない
+        if let Outcome::Walked | Outcome::GoalReached | Outcome::ActivityChanged | Outcome::DayChanged = outcome {
+            PedometerView::render_dirty(display, theme::get_theme(game.dark), tracker, &game.daily_step_count());
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,36 @@
+use views::{
+    helpers::theme,
+    progress_view::ProgressView,
+};
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+use models::game::{
+    Game,
+    Outcome,
+};
+
+pub struct ProgressViewController;
+
+impl ProgressViewController {
+    #[allow(unused_must_use)]
+    pub fn watch<T>(
+        display: &mut T,
+        game: &Game,
+        outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if let Outcome::Walked
+        | Outcome::GoalReached
+        | Outcome::GoalChanged
+        | Outcome::ActivityChanged
+        | Outcome::DayChanged = outcome
+        {
+            ProgressView::render(display, theme::get_theme(game.dark), &game.progress());
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use models::{
+    event::Event,
+    game::Game,
+    save::{
+        Autosave,
+        SaveData,
+        SaveError,
+    },
+    storage::Storage,
+};
+
+pub struct SaveController;
+
+impl SaveController {
+    // まとめて受け取った出来事のどれかでセーブが必要なら、1回だけセーブする。
+    // 失敗したらエラーを返し、次の出来事でセーブし直す
+    pub fn watch<S>(
+        storage: &mut S,
+        autosave: &mut Autosave,
+        game: &Game,
+        events: &[Event],
+    ) -> Result<(), SaveError<S::Error>>
+    where
+        S: Storage,
+    {
+        let step_count = game.pedometer.step_count;
+
+        if events.iter().any(|event| autosave.check(*event, step_count)) {
+            return autosave.finish(SaveData::from_game(game).save(storage));
+        }
+
+        Ok(())
+    }
+}
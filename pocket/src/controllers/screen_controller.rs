@@ -0,0 +1,71 @@
+use crate::controllers::page_controller::PageController;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+use models::{
+    game::{
+        Game,
+        Outcome,
+    },
+    target::TargetPosition,
+};
+use views::{
+    clock_view::ClockView,
+    helpers::{
+        compositor::{
+            Compositor,
+            FlushTarget,
+        },
+        dirty::DirtyTracker,
+        screen,
+        theme,
+    },
+    navigation_view::NavigationView,
+    pedometer_view::PedometerView,
+    progress_view::ProgressView,
+};
+
+pub struct ScreenController;
+
+impl ScreenController {
+    pub fn watch<T>(
+        display: &mut T,
+        compositor: &mut Compositor,
+        step_count: &mut DirtyTracker<i32>,
+        targets: &mut DirtyTracker<TargetPosition>,
+        game: &Game,
+        outcome: Outcome,
+    )
+    where
+        T: DrawTarget<Rgb565> + FlushTarget,
+    {
+        // 色が変わったり時計を合わせたりしたら画面全体を描き直す
+        if let Outcome::ThemeChanged | Outcome::TimeSet = outcome {
+            ScreenController::render(display, compositor, step_count, targets, game);
+        }
+    }
+    // 画面全体を今の色で描く。ページは今のページを開き直したように描く
+    #[allow(unused_must_use)]
+    pub fn render<T>(
+        display: &mut T,
+        compositor: &mut Compositor,
+        step_count: &mut DirtyTracker<i32>,
+        targets: &mut DirtyTracker<TargetPosition>,
+        game: &Game,
+    )
+    where
+        T: DrawTarget<Rgb565> + FlushTarget,
+    {
+        let theme = theme::get_theme(game.dark);
+
+        screen::clear_screen(display, theme);
+        NavigationView::render(display, theme, game.navigation.focus);
+        ClockView::render(display, theme, &game.time);
+        step_count.invalidate();
+        PedometerView::render_dirty(display, theme, step_count, &game.daily_step_count());
+        ProgressView::render(display, theme, &game.progress());
+        PageController::watch(display, compositor, targets, game, Outcome::Routed(game.router.route));
+    }
+}
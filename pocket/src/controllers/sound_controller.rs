@@ -0,0 +1,35 @@
+use crate::helpers::buzzer::{
+    beep,
+    fanfare,
+};
+
+use models::event::Event;
+use wio_terminal::{
+    hal::{
+        delay::Delay,
+        pwm::Tcc0Pwm,
+    },
+    prelude::*,
+};
+
+pub struct SoundController;
+
+impl SoundController {
+    pub fn watch(
+        buzzer: &mut Tcc0Pwm,
+        delay: &mut Delay,
+        events: &[Event],
+    ) {
+        // Levelアップと目標の達成が同時に起きてもファンファーレは1回だけ鳴らす
+        let is_celebrated = events
+            .iter()
+            .any(|event| matches!(event, Event::LevelUp(_) | Event::GoalReached(_)));
+
+        if is_celebrated {
+            fanfare(buzzer, delay);
+        } else if events.iter().any(|event| matches!(event, Event::ItemFound(_))) {
+            // アイテムを見つけたら短く鳴らす
+            beep(buzzer, delay, 1568.hz(), 50u16);
+        }
+    }
+}
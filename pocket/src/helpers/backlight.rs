@@ -0,0 +1,117 @@
+use core::cell::RefCell;
+use cortex_m::{
+    interrupt::{
+        free,
+        Mutex,
+    },
+    peripheral::NVIC,
+};
+use models::settings::MAX_BRIGHTNESS;
+use wio_terminal::{
+    hal::{
+        clock::GenericClockController,
+        gpio::{
+            Output,
+            Pc5,
+            PushPull,
+        },
+        timer::TimerCounter4,
+    },
+    pac::{
+        interrupt,
+        MCLK,
+        TC4,
+    },
+    prelude::*,
+};
+
+// 1周期をMAX_BRIGHTNESS個に分けた1つ分の時間(マイクロ秒)。200Hzでちらつかないようにする
+const SLOT_US: u32 = 1_000_000 / 200 / MAX_BRIGHTNESS as u32;
+
+// 割り込みハンドラが使うもの
+struct Context {
+    pin: Pc5<Output<PushPull>>,
+    timer: TimerCounter4,
+    level: i32,
+    // 周期の中の今の位置
+    slot: i32,
+}
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> = Mutex::new(RefCell::new(None));
+
+// バックライトのピンはPWMにつながっていないので、タイマー割り込みで点けたり消したりして明るさを変える。
+// 消すときと一番明るいときは割り込みを止めておく
+pub struct Backlight {
+    level: i32,
+}
+
+impl Backlight {
+    pub fn start(
+        mut pin: Pc5<Output<PushPull>>,
+        tc4: TC4,
+        clocks: &mut GenericClockController,
+        mclk: &mut MCLK,
+    ) -> Backlight {
+        pin.set_high().unwrap();
+
+        let gclk0 = clocks.gclk0();
+        let timer_clock = clocks.tc4_tc5(&gclk0).unwrap();
+        let mut timer = TimerCounter4::tc4_(&timer_clock, tc4, mclk);
+        timer.start(SLOT_US.us());
+
+        free(|cs| {
+            CONTEXT.borrow(cs).replace(Some(Context {
+                pin,
+                timer,
+                level: MAX_BRIGHTNESS,
+                slot: 0,
+            }));
+        });
+
+        unsafe {
+            NVIC::unmask(interrupt::TC4);
+        }
+
+        Backlight { level: MAX_BRIGHTNESS }
+    }
+    // 0からMAX_BRIGHTNESSまでの明るさにする。0なら消す
+    pub fn set(&mut self, level: i32) {
+        if level == self.level {
+            return;
+        }
+        self.level = level;
+
+        free(|cs| {
+            if let Some(context) = CONTEXT.borrow(cs).borrow_mut().as_mut() {
+                context.level = level;
+                if level <= 0 {
+                    context.timer.disable_interrupt();
+                    context.pin.set_low().unwrap();
+                } else if level >= MAX_BRIGHTNESS {
+                    context.timer.disable_interrupt();
+                    context.pin.set_high().unwrap();
+                } else {
+                    context.timer.enable_interrupt();
+                }
+            }
+        });
+    }
+}
+
+#[interrupt]
+fn TC4() {
+    free(|cs| {
+        if let Some(context) = CONTEXT.borrow(cs).borrow_mut().as_mut() {
+            // 割り込みフラグを下ろす
+            let _ = context.timer.wait();
+
+            // 周期の最初のlevel個の間だけ点ける
+            context.slot = (context.slot + 1) % MAX_BRIGHTNESS;
+            if context.slot < context.level {
+                context.pin.set_high().unwrap();
+            } else {
+                context.pin.set_low().unwrap();
+            }
+        }
+    });
+}
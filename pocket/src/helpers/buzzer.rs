@@ -14,4 +14,14 @@ pub fn beep<P: Into<Hertz>>(
     buzzer_pwm.enable(Channel::_4);
     delay.delay_ms(duration_ms);
     buzzer_pwm.disable(Channel::_4);
-}
\ No newline at end of file
+}
+
+// Levelアップしたときのファンファーレ
+pub fn fanfare(
+    buzzer_pwm: &mut Tcc0Pwm,
+    delay: &mut Delay,
+) {
+    beep(buzzer_pwm, delay, 1047.hz(), 100u16);
+    beep(buzzer_pwm, delay, 1319.hz(), 100u16);
+    beep(buzzer_pwm, delay, 1568.hz(), 300u16);
+}
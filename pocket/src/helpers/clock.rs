@@ -0,0 +1,51 @@
+use models::clock::{
+    Clock,
+    DateTime,
+};
+use wio_terminal::hal::rtc::{
+    Datetime,
+    Rtc,
+};
+
+// RTCの年は2000年からの経過年で数える
+const RTC_EPOCH_YEAR: u16 = 2000;
+
+// SAMD51のRTCをカレンダーモードで動かす時計。
+// 電池がないので電源を入れるたびに与えた日時から数え直す
+pub struct RtcClock {
+    rtc: Rtc,
+}
+
+impl RtcClock {
+    pub fn new(mut rtc: Rtc, now: DateTime) -> RtcClock {
+        rtc.clock_mode();
+        let mut clock = RtcClock { rtc };
+        clock.set(now);
+        clock
+    }
+}
+
+impl Clock for RtcClock {
+    fn now(&mut self) -> DateTime {
+        let time = self.rtc.current_time();
+
+        DateTime {
+            year: RTC_EPOCH_YEAR + time.year as u16,
+            month: time.month,
+            day: time.day,
+            hour: time.hours,
+            minute: time.minutes,
+            second: time.seconds,
+        }
+    }
+    fn set(&mut self, now: DateTime) {
+        self.rtc.set_time(Datetime {
+            seconds: now.second,
+            minutes: now.minute,
+            hours: now.hour,
+            day: now.day,
+            month: now.month,
+            year: (now.year - RTC_EPOCH_YEAR) as u8,
+        });
+    }
+}
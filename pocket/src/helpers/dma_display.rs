@@ -0,0 +1,200 @@
+use core::{
+    iter,
+    ptr,
+};
+use cortex_m::{
+    asm,
+    singleton,
+};
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    style::{
+        PrimitiveStyle,
+        Styled,
+    },
+    DrawTarget,
+};
+use views::helpers::{
+    compositor::FlushTarget,
+    screen,
+};
+use wio_terminal::{
+    pac::{
+        DMAC,
+        MCLK,
+        PORT,
+        SERCOM7,
+    },
+    LCD,
+};
+
+// 1つの帯の画素数。画面幅で16行ずつ描いて送る
+pub const BAND_PIXELS: usize = screen::SCREEN_WIDTH as usize * 16;
+// 使うDMAのチャンネル
+const CHANNEL: usize = 0;
+// LCDのCSはPB21
+const CS_PIN: u32 = 21;
+
+// 転送記述子のBTCTRL
+const BTCTRL_VALID: u16 = 1 << 0;
+const BTCTRL_SRCINC: u16 = 1 << 10;
+
+// DMACが読む転送記述子。16バイト境界に置く
+#[repr(C, align(16))]
+#[derive(Clone, Copy)]
+struct Descriptor {
+    btctrl: u16,
+    btcnt: u16,
+    srcaddr: u32,
+    dstaddr: u32,
+    descaddr: u32,
+}
+
+impl Descriptor {
+    const EMPTY: Descriptor = Descriptor {
+        btctrl: 0,
+        btcnt: 0,
+        srcaddr: 0,
+        dstaddr: 0,
+        descaddr: 0,
+    };
+}
+
+// 画素を1つずつ送るILI9341のドライバに、帯をまとめてDMAで送る機能を足す。
+// 書き込む範囲とコマンドはドライバで送り、画素のデータだけをDMACでSERCOM7へ流す
+pub struct DmaDisplay {
+    lcd: LCD,
+    dmac: DMAC,
+    // 0番目がチャンネル0の記述子、1番目がDMACの書き戻し先
+    descriptors: &'static mut [Descriptor; 2],
+    // 転送中か
+    busy: bool,
+}
+
+impl DmaDisplay {
+    pub fn new(lcd: LCD, dmac: DMAC, mclk: &mut MCLK) -> DmaDisplay {
+        mclk.ahbmask.modify(|_, w| w.dmac_().set_bit());
+
+        let descriptors = singleton!(: [Descriptor; 2] = [Descriptor::EMPTY; 2]).unwrap();
+
+        dmac.ctrl.modify(|_, w| w.dmaenable().clear_bit());
+        dmac.ctrl.modify(|_, w| w.swrst().set_bit());
+        while dmac.ctrl.read().swrst().bit_is_set() {}
+
+        dmac.baseaddr.write(|w| unsafe { w.baseaddr().bits(&descriptors[0] as *const _ as u32) });
+        dmac.wrbaddr.write(|w| unsafe { w.wrbaddr().bits(&descriptors[1] as *const _ as u32) });
+        dmac.ctrl.write(|w| {
+            w.dmaenable().set_bit()
+                .lvlen0().set_bit()
+                .lvlen1().set_bit()
+                .lvlen2().set_bit()
+                .lvlen3().set_bit()
+        });
+
+        DmaDisplay {
+            lcd,
+            dmac,
+            descriptors,
+            busy: false,
+        }
+    }
+    // 送っている帯があれば送り終わるまで待ち、ドライバが使える状態に戻す
+    pub fn wait(&mut self) {
+        if !self.busy {
+            return;
+        }
+
+        let channel = &self.dmac.channel[CHANNEL];
+        while channel.chintflag.read().tcmpl().bit_is_clear()
+            && channel.chintflag.read().terr().bit_is_clear()
+        {}
+        channel.chintflag.write(|w| w.tcmpl().set_bit().terr().set_bit());
+
+        // 最後のバイトが出きってからCSを上げる
+        let spim = unsafe { (*SERCOM7::ptr()).spim() };
+        while spim.intflag.read().txc().bit_is_clear() {}
+        unsafe { (*PORT::ptr()).group1.outset.write(|w| w.bits(1 << CS_PIN)) };
+
+        // 送っている間に受信したデータを読み捨てる。オーバーランが残るとドライバの書き込みが失敗する
+        while spim.intflag.read().rxc().bit_is_set() {
+            spim.data.read();
+        }
+        spim.status.write(|w| w.bufovf().set_bit());
+
+        self.busy = false;
+    }
+    fn start(&mut self, pixels: &[u16]) {
+        let length = pixels.len() * 2;
+        assert!(length <= u16::MAX as usize);
+
+        // 送り元のアドレスは最後のバイトの次を指す
+        let spim = unsafe { (*SERCOM7::ptr()).spim() };
+        let descriptor = Descriptor {
+            btctrl: BTCTRL_VALID | BTCTRL_SRCINC,
+            btcnt: length as u16,
+            srcaddr: pixels.as_ptr() as u32 + length as u32,
+            dstaddr: &spim.data as *const _ as u32,
+            descaddr: 0,
+        };
+        unsafe { ptr::write_volatile(&mut self.descriptors[CHANNEL], descriptor) };
+        asm::dsb();
+
+        // メモリ書き込みのコマンドの後はD/Cがデータのままなので、CSを下げれば続きの画素として受け取られる
+        unsafe { (*PORT::ptr()).group1.outclr.write(|w| w.bits(1 << CS_PIN)) };
+        self.dmac.channel[CHANNEL].chctrla.write(|w| {
+            w.trigsrc().sercom7_tx()
+                .trigact().burst()
+                .enable().set_bit()
+        });
+
+        self.busy = true;
+    }
+}
+
+impl FlushTarget for DmaDisplay {
+    type Error = <LCD as DrawTarget<Rgb565>>::Error;
+
+    // Compositorはバッファを交互に使うので、送っている間にpixelsが書き換えられることはない
+    fn flush(&mut self, area: &Rectangle, pixels: &mut [u16]) -> Result<(), Self::Error> {
+        self.wait();
+
+        // ILI9341は上位バイトから受け取るので、バイト順を入れ替えておく
+        for pixel in pixels.iter_mut() {
+            *pixel = pixel.to_be();
+        }
+
+        // 書き込む範囲とメモリ書き込みのコマンドだけをドライバで送る
+        self.lcd.draw_iter(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            area.bottom_right.x as u16,
+            area.bottom_right.y as u16,
+            iter::empty(),
+        )?;
+        self.start(pixels);
+        Ok(())
+    }
+}
+
+// 帯を送っている間はドライバで描けないので、送り終えてから描く
+impl DrawTarget<Rgb565> for DmaDisplay {
+    type Error = <LCD as DrawTarget<Rgb565>>::Error;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Self::Error> {
+        self.wait();
+        self.lcd.draw_pixel(pixel)
+    }
+    fn draw_rectangle(&mut self, item: &Styled<Rectangle, PrimitiveStyle<Rgb565>>) -> Result<(), Self::Error> {
+        self.wait();
+        self.lcd.draw_rectangle(item)
+    }
+    fn size(&self) -> Size {
+        self.lcd.size()
+    }
+    fn clear(&mut self, color: Rgb565) -> Result<(), Self::Error> {
+        self.wait();
+        self.lcd.clear(color)
+    }
+}
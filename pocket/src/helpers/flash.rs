@@ -0,0 +1,134 @@
+use models::flash::Flash;
+use wio_terminal::pac::{
+    nvmctrl::ctrlb::CMD_AW,
+    NVMCTRL,
+};
+
+// セーブデータ用に確保するフラッシュ末尾の4ブロック(32KB)。
+// pocket/memory.xでプログラムの領域から外しているので、変えるときは合わせて変える
+const FLASH_ADDRESS: u32 = 0x0008_0000 - BLOCK_SIZE * BLOCK_COUNT;
+const BLOCK_SIZE: u32 = 8 * 1024;
+const BLOCK_COUNT: u32 = 4;
+// 一度に書き込めるクアッドワードの大きさ
+const QUAD_WORD_SIZE: usize = 16;
+
+#[derive(Debug)]
+pub enum NvmError {
+    // アドレスが不正
+    Address,
+    // 書き込みに失敗した
+    Program,
+    // ロックされた領域への書き込み
+    Lock,
+    // NVMのエラー
+    Nvm,
+}
+
+// SAMD51のNVMCTRLでフラッシュを読み書きする。
+// 消去の単位であるブロックをページとして扱う
+pub struct NvmFlash {
+    nvmctrl: NVMCTRL,
+}
+
+impl NvmFlash {
+    pub fn new(nvmctrl: NVMCTRL) -> NvmFlash {
+        // ページバッファを明示的に書き込むマニュアルモードにする
+        nvmctrl.ctrla.modify(|_, w| w.wmode().man());
+        NvmFlash { nvmctrl }
+    }
+    fn wait_ready(&self) {
+        while self.nvmctrl.status.read().ready().bit_is_clear() {}
+    }
+    fn check_range(&self, address: usize, length: usize) -> Result<(), NvmError> {
+        if address + length > (BLOCK_SIZE * BLOCK_COUNT) as usize {
+            Err(NvmError::Address)
+        } else {
+            Ok(())
+        }
+    }
+    fn command(&mut self, command: CMD_AW, address: u32) -> Result<(), NvmError> {
+        self.wait_ready();
+        self.nvmctrl.addr.write(|w| unsafe { w.addr().bits(address) });
+        self.nvmctrl.ctrlb.write(|w| w.cmdex().key().cmd().variant(command));
+        self.wait_ready();
+
+        let flags = self.nvmctrl.intflag.read();
+        let result = if flags.addre().bit_is_set() {
+            Err(NvmError::Address)
+        } else if flags.proge().bit_is_set() {
+            Err(NvmError::Program)
+        } else if flags.locke().bit_is_set() {
+            Err(NvmError::Lock)
+        } else if flags.nvme().bit_is_set() {
+            Err(NvmError::Nvm)
+        } else {
+            Ok(())
+        };
+
+        // エラーフラグをクリアする
+        self.nvmctrl.intflag.write(|w| {
+            w.done().set_bit()
+                .addre().set_bit()
+                .proge().set_bit()
+                .locke().set_bit()
+                .nvme().set_bit()
+        });
+
+        result
+    }
+}
+
+impl Flash for NvmFlash {
+    type Error = NvmError;
+
+    fn page_size(&self) -> usize {
+        BLOCK_SIZE as usize
+    }
+    fn page_count(&self) -> usize {
+        BLOCK_COUNT as usize
+    }
+    fn read(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.check_range(address, buffer.len())?;
+        self.wait_ready();
+
+        for (offset, byte) in buffer.iter_mut().enumerate() {
+            let pointer = (FLASH_ADDRESS as usize + address + offset) as *const u8;
+            *byte = unsafe { core::ptr::read_volatile(pointer) };
+        }
+
+        Ok(())
+    }
+    // addressはクアッドワード境界に揃っている必要がある
+    fn write(&mut self, address: usize, data: &[u8]) -> Result<(), Self::Error> {
+        self.check_range(address, data.len())?;
+        if address % QUAD_WORD_SIZE != 0 {
+            return Err(NvmError::Address);
+        }
+
+        for (index, chunk) in data.chunks(QUAD_WORD_SIZE).enumerate() {
+            let quad_word_address = FLASH_ADDRESS + (address + index * QUAD_WORD_SIZE) as u32;
+
+            // 足りない分は書き込んでも変化しない0xFFで埋める
+            let mut quad_word = [0xFF; QUAD_WORD_SIZE];
+            quad_word[..chunk.len()].copy_from_slice(chunk);
+
+            // ページバッファには32bit単位で書き込む
+            self.command(CMD_AW::PBC, quad_word_address)?;
+            for (word_index, word) in quad_word.chunks(4).enumerate() {
+                let pointer = (quad_word_address as usize + word_index * 4) as *mut u32;
+                let value = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+                unsafe { core::ptr::write_volatile(pointer, value) };
+            }
+            self.command(CMD_AW::WQW, quad_word_address)?;
+        }
+
+        Ok(())
+    }
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error> {
+        if page >= BLOCK_COUNT as usize {
+            return Err(NvmError::Address);
+        }
+
+        self.command(CMD_AW::EB, FLASH_ADDRESS + page as u32 * BLOCK_SIZE)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::helpers::wakeup;
+
+use accelerometer::vector::F32x3;
+use core::cell::RefCell;
+use cortex_m::{
+    interrupt::{
+        free,
+        Mutex,
+    },
+    peripheral::NVIC,
+    singleton,
+};
+use heapless::{
+    consts::*,
+    spsc::{
+        Consumer,
+        Producer,
+        Queue,
+    },
+};
+use models::pedometer::Sample;
+use wio_terminal::{
+    hal::{
+        clock::GenericClockController,
+        gpio::{
+            Pa12,
+            Pa13,
+            PfD,
+            Port,
+        },
+        hal::blocking::i2c::{
+            Write,
+            WriteRead,
+        },
+        sercom::{
+            I2CError,
+            I2CMaster4,
+            PadPin,
+            Sercom4Pad0,
+            Sercom4Pad1,
+        },
+    },
+    pac::{
+        interrupt,
+        Interrupt,
+        MCLK,
+        SERCOM4,
+    },
+    prelude::*,
+    Accelerometer,
+};
+
+// 加速度センサがサンプルを取る間隔(ミリ秒)。50Hzで取る
+pub const SAMPLE_INTERVAL_MS: u32 = 20;
+
+// Wio Terminalに載っているLIS3DHのI2Cのアドレスとレジスタ
+const ADDRESS: u8 = 0x18;
+const WHO_AM_I: u8 = 0x0F;
+const DEVICE_ID: u8 = 0x33;
+const CTRL_REG1: u8 = 0x20;
+const CTRL_REG3: u8 = 0x22;
+const CTRL_REG4: u8 = 0x23;
+const CTRL_REG5: u8 = 0x24;
+const OUT_X_L: u8 = 0x28;
+const FIFO_CTRL_REG: u8 = 0x2E;
+const FIFO_SRC_REG: u8 = 0x2F;
+// 50Hzで3軸とも取る
+const ODR_50HZ_XYZ: u8 = 0x47;
+// 読み出し中に値が変わらないようにし、12ビットの高分解能で±2gを測る
+const BDU_HR: u8 = 0x88;
+const FIFO_EN: u8 = 0x40;
+// FIFOが溜まったらINT1に知らせる
+const I1_WTM: u8 = 0x04;
+const FIFO_BYPASS: u8 = 0x00;
+const FIFO_STREAM: u8 = 0x80;
+const FIFO_SAMPLES_MASK: u8 = 0x1F;
+// 続けて読むときはアドレスの最上位ビットを立てる
+const AUTO_INCREMENT: u8 = 0x80;
+// この数より多く溜まったら割り込みで読み出す。0.5秒ごとに起きる
+const WATERMARK: u8 = 24;
+// 高分解能で±2gのときの1LSBあたりのg。値は左詰めの12ビット
+const SCALE: f32 = 0.001;
+
+type I2c = I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>>;
+// 食べる・投げるアニメーションで数秒止まっても溢れないだけ溜めておく
+type Capacity = U512;
+
+// 割り込みハンドラが使うもの
+struct Context {
+    i2c: I2c,
+    // サンプリングを始めてからの経過時間
+    timestamp_ms: u32,
+    producer: Producer<'static, Sample, Capacity>,
+    // FIFOを空にできずに割り込みを止めている
+    stalled: bool,
+}
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> = Mutex::new(RefCell::new(None));
+
+// 加速度センサのFIFOに溜めたサンプルを、溜まったときの割り込みでまとめて読み、時刻つきでキューに溜める。
+// マイコンは眠っていてもよく、メインループが描画やブザーで止まっていても歩数を取りこぼさない
+pub struct Sampler {
+    consumer: Consumer<'static, Sample, Capacity>,
+}
+
+impl Sampler {
+    // wakeup::initの後に呼ぶ
+    pub fn start(
+        pins: Accelerometer,
+        sercom4: SERCOM4,
+        clocks: &mut GenericClockController,
+        mclk: &mut MCLK,
+        port: &mut Port,
+    ) -> Sampler {
+        let gclk0 = clocks.gclk0();
+        let mut i2c = I2CMaster4::new(
+            &clocks.sercom4_core(&gclk0).unwrap(),
+            400.khz(),
+            sercom4,
+            mclk,
+            pins.sda.into_pad(port),
+            pins.scl.into_pad(port),
+        );
+
+        let mut id = [0];
+        i2c.write_read(ADDRESS, &[WHO_AM_I], &mut id).unwrap();
+        assert_eq!(id[0], DEVICE_ID);
+
+        for (register, value) in [
+            (CTRL_REG1, ODR_50HZ_XYZ),
+            (CTRL_REG4, BDU_HR),
+            (CTRL_REG5, FIFO_EN),
+        ]
+        .iter()
+        {
+            i2c.write(ADDRESS, &[*register, *value]).unwrap();
+        }
+        reset_fifo(&mut i2c).unwrap();
+        i2c.write(ADDRESS, &[CTRL_REG3, I1_WTM]).unwrap();
+
+        let queue = singleton!(: Queue<Sample, Capacity> = Queue::new()).unwrap();
+        let (producer, consumer) = queue.split();
+
+        free(|cs| {
+            CONTEXT.borrow(cs).replace(Some(Context {
+                i2c,
+                timestamp_ms: 0,
+                producer,
+                stalled: false,
+            }));
+        });
+
+        wakeup::enable(wakeup::ACCEL_LINE);
+        unsafe {
+            NVIC::unmask(Interrupt::EIC_EXTINT_5);
+        }
+
+        Sampler { consumer }
+    }
+    // 溜まっているサンプルを古い順に取り出す。割り込みを止めていたら、FIFOを空にし直して再開する
+    pub fn drain(&mut self) -> impl Iterator<Item = Sample> + '_ {
+        free(|cs| {
+            if let Some(context) = CONTEXT.borrow(cs).borrow_mut().as_mut() {
+                if context.stalled && reset_fifo(&mut context.i2c).is_ok() {
+                    context.stalled = false;
+                    wakeup::clear(wakeup::ACCEL_LINE);
+                    wakeup::enable(wakeup::ACCEL_LINE);
+                }
+            }
+        });
+
+        let consumer = &mut self.consumer;
+        core::iter::from_fn(move || consumer.dequeue())
+    }
+    // 最後に読んだサンプルの時刻。眠っていた時間を数えるのに使う
+    pub fn timestamp_ms(&self) -> u32 {
+        free(|cs| {
+            CONTEXT
+                .borrow(cs)
+                .borrow()
+                .as_ref()
+                .map_or(0, |context| context.timestamp_ms)
+        })
+    }
+}
+
+impl Context {
+    // FIFOに溜まっている分を1つずつ読む。キューが溢れたときは、そのサンプルを捨てる
+    fn read_fifo(&mut self) -> Result<(), I2CError> {
+        let mut source = [0];
+        self.i2c.write_read(ADDRESS, &[FIFO_SRC_REG], &mut source)?;
+
+        for _ in 0..source[0] & FIFO_SAMPLES_MASK {
+            self.timestamp_ms = self.timestamp_ms.wrapping_add(SAMPLE_INTERVAL_MS);
+
+            let mut bytes = [0; 6];
+            self.i2c.write_read(ADDRESS, &[OUT_X_L | AUTO_INCREMENT], &mut bytes)?;
+            let axis = |index: usize| (i16::from_le_bytes([bytes[index], bytes[index + 1]]) >> 4) as f32 * SCALE;
+            let _ = self.producer.enqueue(Sample {
+                timestamp_ms: self.timestamp_ms,
+                accel: F32x3::new(axis(0), axis(2), axis(4)),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// 一度バイパスにしてFIFOを空にしてから、古いものを捨てながら溜め続けるストリームにする
+fn reset_fifo(i2c: &mut I2c) -> Result<(), I2CError> {
+    i2c.write(ADDRESS, &[FIFO_CTRL_REG, FIFO_BYPASS])?;
+    i2c.write(ADDRESS, &[FIFO_CTRL_REG, FIFO_STREAM | WATERMARK])
+}
+
+#[interrupt]
+fn EIC_EXTINT_5() {
+    free(|cs| {
+        if let Some(context) = CONTEXT.borrow(cs).borrow_mut().as_mut() {
+            // 読めなかったら溜まっている分を捨ててINT1を下げる。INT1がHighのままだと割り込みが続いて抜けられないので、
+            // それもできなければ割り込みを止めて、メインループから直す
+            if context.read_fifo().is_err() && reset_fifo(&mut context.i2c).is_err() {
+                wakeup::disable(wakeup::ACCEL_LINE);
+                context.stalled = true;
+            }
+        }
+    });
+    // 読み出してINT1が下がってからフラグを下ろす
+    wakeup::clear(wakeup::ACCEL_LINE);
+    wakeup::notify();
+}
@@ -0,0 +1,88 @@
+use crate::helpers::wakeup;
+use core::sync::atomic::{
+    AtomicBool,
+    Ordering,
+};
+use cortex_m::{
+    asm,
+    peripheral::NVIC,
+};
+use wio_terminal::{
+    hal::{
+        clock::GenericClockController,
+        sleeping_delay::SleepingDelay,
+        timer::TimerCounter3,
+    },
+    pac::{
+        interrupt,
+        MCLK,
+        PM,
+        TC3,
+    },
+    prelude::*,
+};
+
+// タイマー割り込みが来たことをSleepingDelayに知らせる
+static TIMER_FIRED: AtomicBool = AtomicBool::new(false);
+
+// メインループの待ち方を切り替える。
+// 画面が点いている間はCPUだけを止めてタイマーで起き、消えている間はクロックも止めて割り込みが来るまで眠る
+pub struct Sleeper {
+    delay: SleepingDelay<TimerCounter3>,
+    pm: PM,
+}
+
+impl Sleeper {
+    pub fn new(
+        tc3: TC3,
+        pm: PM,
+        clocks: &mut GenericClockController,
+        mclk: &mut MCLK,
+    ) -> Sleeper {
+        let gclk0 = clocks.gclk0();
+        let timer_clock = clocks.tc2_tc3(&gclk0).unwrap();
+        let timer = TimerCounter3::tc3_(&timer_clock, tc3, mclk);
+
+        unsafe {
+            NVIC::unmask(interrupt::TC3);
+        }
+
+        Sleeper {
+            delay: SleepingDelay::new(timer, &TIMER_FIRED),
+            pm,
+        }
+    }
+    // CPUだけを止めて、ms経ったら起きる。その間も割り込みは受け取る
+    pub fn idle(&mut self, ms: u32) {
+        self.pm.sleepcfg.write(|w| w.sleepmode().idle());
+        while !self.pm.sleepcfg.read().sleepmode().is_idle() {}
+
+        self.delay.delay_ms(ms);
+    }
+    // クロックも止めて、加速度センサのFIFOが溜まるかボタンが押されるまで眠る。
+    // ボタンZを読むためにRTCで起きたときは、押されていなければそのまま眠り直す。
+    // 止まる前にディスプレイへの転送は終えておく
+    pub fn standby(&mut self) {
+        self.pm.sleepcfg.write(|w| w.sleepmode().standby());
+        while !self.pm.sleepcfg.read().sleepmode().is_standby() {}
+
+        wakeup::start_polling();
+        loop {
+            asm::dsb();
+            asm::wfi();
+            if wakeup::take() {
+                break;
+            }
+        }
+        wakeup::stop_polling();
+
+        self.pm.sleepcfg.write(|w| w.sleepmode().idle());
+    }
+}
+
+#[interrupt]
+fn TC3() {
+    // 割り込みフラグを下ろす
+    unsafe { (*TC3::ptr()).count16().intflag.write(|w| w.ovf().set_bit()) };
+    TIMER_FIRED.store(true, Ordering::Relaxed);
+}
@@ -0,0 +1,184 @@
+use core::sync::atomic::{
+    AtomicBool,
+    Ordering,
+};
+use cortex_m::peripheral::NVIC;
+use wio_terminal::pac::{
+    interrupt,
+    Interrupt,
+    EIC,
+    MCLK,
+    PORT,
+    RTC,
+};
+
+// LIS3DHのINT1(PC21)をつないだ外部割り込みの線
+pub const ACCEL_LINE: usize = 5;
+
+// ボタンをつないだ外部割り込みの線とピン。(線, PORTのグループ, ピン)の順に並べる。
+// ボタンZ(PD10)はLIS3DHのINT1と同じ線につながるので、ここには入れずPOLLED_BUTTONで読む
+const BUTTONS: [(usize, usize, usize); 4] = [
+    // ボタンX
+    (3, 3, 8),
+    // ボタンY
+    (4, 3, 9),
+    // 5方向スイッチの左
+    (7, 3, 12),
+    // 5方向スイッチの上
+    (10, 3, 20),
+];
+const ACCEL_PIN: (usize, usize) = (2, 21);
+// 眠っている間はRTCで起きて読むボタンZのピン。(PORTのグループ, ピン)
+const POLLED_BUTTON: (usize, usize) = (3, 10);
+
+// 眠りから覚めてメインループに戻るべきことが起きた
+static WOKEN: AtomicBool = AtomicBool::new(false);
+
+// EIC.CONFIGのSENSE
+const SENSE_FALL: u32 = 0x2;
+const SENSE_HIGH: u32 = 0x4;
+const FILTEN: u32 = 0x8;
+
+// 眠っている間も加速度センサとボタンで起きられるように、外部割り込みを用意する。
+// EICは常に動いている低電力の32kHzのクロックで動かすので、Standbyの間も割り込みを受け取れる。
+// ボタンZはRTCの定期的な割り込みで読むので、RTCを初期化してから呼ぶ
+pub fn init(eic: EIC, mclk: &mut MCLK) {
+    mclk.apbamask.modify(|_, w| w.eic_().set_bit());
+
+    eic.ctrla.modify(|_, w| w.swrst().set_bit());
+    while eic.syncbusy.read().swrst().bit_is_set() {}
+    eic.ctrla.modify(|_, w| w.cksel().set_bit());
+
+    // CONFIGは有効にする前にしか書けないので、使う線をまとめて設定する。
+    // INT1はサンプルを読み出すまでHighのままなので、レベルで受け取る
+    let mut config = [0u32; 2];
+    set_sense(&mut config, ACCEL_LINE, SENSE_HIGH);
+    for (line, _, _) in BUTTONS.iter() {
+        set_sense(&mut config, *line, SENSE_FALL | FILTEN);
+    }
+    for (index, bits) in config.iter().enumerate() {
+        eic.config[index].write(|w| unsafe { w.bits(*bits) });
+    }
+
+    // ボタンは入力として読んだまま、EICにもつなぐ
+    connect(ACCEL_PIN);
+    for (_, group, pin) in BUTTONS.iter() {
+        connect((*group, *pin));
+    }
+
+    eic.ctrla.modify(|_, w| w.enable().set_bit());
+    while eic.syncbusy.read().enable().bit_is_set() {}
+
+    // ボタンの割り込みは起きるためだけに使う
+    for (line, _, _) in BUTTONS.iter() {
+        enable(*line);
+    }
+    unsafe {
+        NVIC::unmask(Interrupt::EIC_EXTINT_3);
+        NVIC::unmask(Interrupt::EIC_EXTINT_4);
+        NVIC::unmask(Interrupt::EIC_EXTINT_7);
+        NVIC::unmask(Interrupt::EIC_EXTINT_10);
+        NVIC::unmask(Interrupt::RTC);
+    }
+}
+
+// 眠っている間、RTCで1/16秒ごとに起きてボタンZを読む。
+// RTCは1024HzのクロックでPERnを2^(n+3)分周するので、PER3で16Hzになる。
+// 短く押しても1/16秒より長く押されていれば拾える
+pub fn start_polling() {
+    let rtc = unsafe { (*RTC::ptr()).mode2() };
+    rtc.intflag.write(|w| w.per3().set_bit());
+    rtc.intenset.write(|w| w.per3().set_bit());
+}
+
+// 起きている間はボタンZをメインループで読むので、RTCで起きるのをやめる
+pub fn stop_polling() {
+    unsafe { (*RTC::ptr()).mode2().intenclr.write(|w| w.per3().set_bit()) };
+}
+
+// 加速度センサかボタンで起きたことを知らせる。割り込みの中から呼ぶ
+pub fn notify() {
+    WOKEN.store(true, Ordering::Relaxed);
+}
+
+// 前に呼んでから、起きたことを知らされたか
+pub fn take() -> bool {
+    WOKEN.swap(false, Ordering::Relaxed)
+}
+
+// 線の割り込みを有効にする
+pub fn enable(line: usize) {
+    unsafe { (*EIC::ptr()).intenset.write(|w| w.bits(1 << line)) };
+}
+
+// 線の割り込みを止める
+pub fn disable(line: usize) {
+    unsafe { (*EIC::ptr()).intenclr.write(|w| w.bits(1 << line)) };
+}
+
+// 線の割り込みフラグを下ろす
+pub fn clear(line: usize) {
+    unsafe { (*EIC::ptr()).intflag.write(|w| w.bits(1 << line)) };
+}
+
+// ボタンZが押されているか
+fn is_polled_button_pressed() -> bool {
+    let (_, pin) = POLLED_BUTTON;
+    unsafe { (*PORT::ptr()).group3.in_.read().bits() & (1 << pin) == 0 }
+}
+
+fn set_sense(config: &mut [u32; 2], line: usize, sense: u32) {
+    config[line / 8] |= sense << (line % 8 * 4);
+}
+
+// ピンを機能A(EIC)につなぐ。INENを立てておけば入力としても読める
+fn connect((group, pin): (usize, usize)) {
+    let port = unsafe { &*PORT::ptr() };
+    let group = match group {
+        0 => &port.group0,
+        1 => &port.group1,
+        2 => &port.group2,
+        _ => &port.group3,
+    };
+
+    group.pmux[pin / 2].modify(|_, w| unsafe {
+        if pin % 2 == 0 {
+            w.pmuxe().bits(0)
+        } else {
+            w.pmuxo().bits(0)
+        }
+    });
+    group.pincfg[pin].modify(|_, w| w.pmuxen().set_bit().inen().set_bit());
+}
+
+#[interrupt]
+fn EIC_EXTINT_3() {
+    clear(3);
+    notify();
+}
+
+#[interrupt]
+fn EIC_EXTINT_4() {
+    clear(4);
+    notify();
+}
+
+#[interrupt]
+fn EIC_EXTINT_7() {
+    clear(7);
+    notify();
+}
+
+#[interrupt]
+fn EIC_EXTINT_10() {
+    clear(10);
+    notify();
+}
+
+#[interrupt]
+fn RTC() {
+    unsafe { (*RTC::ptr()).mode2().intflag.write(|w| w.per3().set_bit()) };
+    if is_polled_button_pressed() {
+        notify();
+    }
+}
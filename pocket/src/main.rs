@@ -2,72 +2,97 @@
 #![no_main]
 
 extern crate models;
+extern crate views;
 
 mod helpers {
+    pub mod backlight;
     pub mod buzzer;
-    pub mod image;
-    pub mod screen;
-}
-mod views {
-    pub mod navigation_view;
-    pub mod pedometer_view;
-    pub mod pages {
-        pub mod eat_page;
-        pub mod fail_page;
-        pub mod game_page;
-        pub mod home_page;
-        pub mod meal_page;
-        pub mod play_page;
-        pub mod success_page;
-        pub mod throw_page;
-    }
+    pub mod clock;
+    pub mod dma_display;
+    pub mod flash;
+    pub mod sampler;
+    pub mod sleeper;
+    pub mod wakeup;
 }
 mod controllers {
+    pub mod clock_view_controller;
+    pub mod input_controller;
     pub mod navigation_view_controller;
     pub mod pedometer_view_controller;
     pub mod page_controller;
+    pub mod progress_view_controller;
+    pub mod save_controller;
+    pub mod screen_controller;
+    pub mod sound_controller;
     pub mod pages {
         pub mod home_page_controller;
         pub mod meal_page_controller;
         pub mod play_page_controller;
         pub mod game_page_controller;
+        pub mod scene_page_controller;
+        pub mod stats_page_controller;
     }
 }
 
-use crate::helpers::screen;
-use crate::views::{
-    navigation_view::NavigationView,
-    pedometer_view::PedometerView,
-    pages::home_page::HomePage,
+use crate::helpers::{
+    backlight::Backlight,
+    buzzer::beep,
+    clock::RtcClock,
+    dma_display::{
+        DmaDisplay,
+        BAND_PIXELS,
+    },
+    flash::NvmFlash,
+    sampler::Sampler,
+    sleeper::Sleeper,
+    wakeup,
 };
 use crate::controllers::{
+    clock_view_controller::ClockViewController,
+    pages::home_page_controller::HomePageController,
+    input_controller::InputController,
     navigation_view_controller::NavigationViewController,
     pedometer_view_controller::PedometerViewController,
     page_controller::PageController,
+    progress_view_controller::ProgressViewController,
+    save_controller::SaveController,
+    screen_controller::ScreenController,
+    sound_controller::SoundController,
 };
 
-use accelerometer::Accelerometer;
+use cortex_m::singleton;
+use heapless::{
+    consts::*,
+    Vec,
+};
 use models::{
-    bet::Bet,
-    character::Character,
-    meal::Meal,
-    navigation::Navigation,
-    pedometer::Pedometer,
-    rice_ball::RiceBall,
-    router::{
-        Route,
-        Router,
+    clock::Clock,
+    event::Event,
+    game::{
+        Game,
+        Outcome,
+    },
+    journal::Journal,
+    power::Wait,
+    save::{
+        Autosave,
+        SaveData,
     },
-    shuriken::Shuriken,
-    target::Target,
 };
 use panic_halt as _;
+use views::helpers::{
+    compositor::Compositor,
+    dirty::DirtyTracker,
+    image,
+    sprite::AnimatedSprite,
+};
 use wio_terminal::{
     entry,
     hal::{
         clock::GenericClockController,
         delay::Delay,
-        pwm::Channel
+        pwm::Channel,
+        rtc::Rtc,
     },
     pac::{
         CorePeripherals,
@@ -77,6 +102,13 @@ use wio_terminal::{
     Pins,
 };
 
+// メインループの間隔(ミリ秒)
+const LOOP_INTERVAL_MS: u32 = 100;
+// オートセーブする歩数の間隔。
+// 1日1万歩で約1,000回、毎時のセーブなどを足して1日約1,100回書く。ジャーナルは4ブロックで224エントリなので
+// 1ブロックあたり1日約5回の消去になり、SAMD51のフラッシュの書き換え寿命(10,000回)まで約5年もつ
+const AUTOSAVE_INTERVAL_STEPS: i32 = 10;
+
 #[entry]
 fn main() -> ! {
     let mut peripherals = Peripherals::take().unwrap();
@@ -95,7 +127,7 @@ fn main() -> ! {
     let mut delay = Delay::new(core.SYST, &mut clocks);
 
     // ディスプレイドライバの初期化
-    let (mut display, _backlight) = sets
+    let (lcd, backlight) = sets
         .display
         .init(
             &mut clocks,
@@ -107,6 +139,15 @@ fn main() -> ! {
         )
         .unwrap();
 
+    // ページの描画はRAMで帯ごとに描き、DMAで送っている間に次の帯を描く
+    let mut display = DmaDisplay::new(lcd, peripherals.DMAC, &mut peripherals.MCLK);
+    let front = singleton!(: [u16; BAND_PIXELS] = [0; BAND_PIXELS]).unwrap();
+    let back = singleton!(: [u16; BAND_PIXELS] = [0; BAND_PIXELS]).unwrap();
+    let mut compositor = Compositor::new(front, back);
+
+    // 操作が無いときはバックライトを暗くしたり消したりする
+    let mut backlight = Backlight::start(backlight, peripherals.TC4, &mut clocks, &mut peripherals.MCLK);
+
     // ブザーの初期化
     let mut buzzer = sets.buzzer.init(
         &mut clocks,
@@ -119,11 +160,13 @@ fn main() -> ! {
     buzzer.disable(Channel::_4);
 
     // ボタンのGPIOを初期化
-    let switch_x = sets.buttons.switch_x.into_floating_input(&mut sets.port);
-    let switch_y = sets.buttons.switch_y.into_floating_input(&mut sets.port);
-    let switch_u = sets.buttons.switch_u.into_floating_input(&mut sets.port);
-    let switch_b = sets.buttons.switch_b.into_floating_input(&mut sets.port);
-    let switch_z = sets.buttons.switch_z.into_floating_input(&mut sets.port);
+    let input = InputController {
+        switch_x: sets.buttons.switch_x.into_floating_input(&mut sets.port),
+        switch_y: sets.buttons.switch_y.into_floating_input(&mut sets.port),
+        switch_z: sets.buttons.switch_z.into_floating_input(&mut sets.port),
+        switch_b: sets.buttons.switch_b.into_floating_input(&mut sets.port),
+        switch_u: sets.buttons.switch_u.into_floating_input(&mut sets.port),
+    };
 
     // UARTドライバオブジェクトの初期化
     let mut _serial = sets.uart.init(
@@ -134,88 +177,118 @@ fn main() -> ! {
         &mut sets.port,
     );
 
-    // 加速度センサドライバオブジェクトの初期化
-    let mut accel = sets.accelerometer.init(
-        &mut clocks,
+    // セーブデータの読み込み
+    let mut storage = Journal::mount(NvmFlash::new(peripherals.NVMCTRL)).unwrap();
+    let mut autosave = Autosave::new(AUTOSAVE_INTERVAL_STEPS);
+
+    // ゲームの初期化
+    let mut game = Game::new();
+    if let Ok(save_data) = SaveData::load(&mut storage) {
+        save_data.restore(&mut game);
+        autosave.last_step_count = game.pedometer.step_count;
+    }
+
+    // RTCの初期化。1024Hzのクロックはメインクロックの初期化で用意されている。
+    // 電源を切っている間の時間は分からないので、最後にセーブした日時から数える。時計は1時間ごとと歩いたときなどにセーブされる。
+    // ずれていたらStatsで合わせ直す
+    let rtc = Rtc::new(peripherals.RTC, 1024.hz(), &mut peripherals.MCLK);
+    let mut clock = RtcClock::new(rtc, game.time);
+
+    // 眠っている間も加速度センサとボタンで起きられるようにして、加速度センサのサンプリングを始める
+    wakeup::init(peripherals.EIC, &mut peripherals.MCLK);
+    let mut sampler = Sampler::start(
+        sets.accelerometer,
         peripherals.SERCOM4,
+        &mut clocks,
         &mut peripherals.MCLK,
         &mut sets.port,
     );
+    let mut sleeper = Sleeper::new(peripherals.TC3, peripherals.PM, &mut clocks, &mut peripherals.MCLK);
+    // 前の周回で経った時間
+    let mut elapsed_ms = LOOP_INTERVAL_MS;
 
-    // Routerの初期化
-    let mut router = Router::new(Route::Home);
+    // 初期画面の描画。歩数とGameの的は変わったところだけを描き直す
+    let mut step_count = DirtyTracker::new();
+    let mut targets = DirtyTracker::new();
+    ScreenController::render(&mut display, &mut compositor, &mut step_count, &mut targets, &game);
+    let mut sprite = AnimatedSprite::new(image::get_character_frames(&image::get_mood_state(&game.mood())));
 
-    // ナビゲーションの初期化
-    let mut navigation = Navigation::new(Route::Home);
-
-    // 歩数計の初期化
-    let mut pedometer = Pedometer::new();
+    loop {
+        // 画面が消えている間は描画を省き、点いたときにまとめて描き直す
+        let was_visible = game.power.is_visible();
 
-    // キャラクターの初期化
-    let mut character = Character::new();
+        // ボタン入力をゲームに渡して、起きたことを描画する
+        for event in input.watch() {
+            if let Some(outcome) = game.handle(event) {
+                // Statsで時計を合わせたら、次に読む前にRTCも合わせる
+                if outcome == Outcome::TimeSet {
+                    clock.set(game.time);
+                }
+                beep(&mut buzzer, &mut delay, 800.hz(), 200u16);
+                ScreenController::watch(&mut display, &mut compositor, &mut step_count, &mut targets, &game, outcome);
+                NavigationViewController::watch(&mut display, &game, outcome);
+                ProgressViewController::watch(&mut display, &game, outcome);
+                PageController::watch(&mut display, &mut compositor, &mut targets, &game, outcome);
+            }
+        }
 
-    // おにぎりの初期化
-    let mut rice_ball = RiceBall::new();
+        // 前回から溜まったサンプルで歩数を数える
+        if let Some(outcome) = game.sample(sampler.drain()) {
+            if was_visible {
+                PedometerViewController::watch(&mut display, &mut step_count, &game, outcome);
+                ProgressViewController::watch(&mut display, &game, outcome);
+                PageController::watch(&mut display, &mut compositor, &mut targets, &game, outcome);
+            }
+        }
 
-    // 食事の初期化
-    let mut meal = Meal::new();
+        // ボタンか歩いたことで画面が点いたら、消えている間に変わった分も含めて画面全体を描き直す
+        if !was_visible && game.power.is_visible() {
+            ScreenController::render(&mut display, &mut compositor, &mut step_count, &mut targets, &game);
+        }
 
-    // BETの初期化
-    let mut bet = Bet::new();
+        // 時間を進める
+        if let Some(outcome) = game.tick(elapsed_ms) {
+            if game.power.is_visible() {
+                NavigationViewController::watch(&mut display, &game, outcome);
+                PageController::watch(&mut display, &mut compositor, &mut targets, &game, outcome);
+            }
+        }
+        if game.power.is_visible() {
+            HomePageController::animate(&mut display, &mut sprite, &game, elapsed_ms);
+        }
 
-    // 手裏剣の初期化
-    let mut shuriken = Shuriken::new();
+        // 時計を読んで、時刻と日付の変化を描画する
+        if let Some(outcome) = game.update_time(clock.now()) {
+            if game.power.is_visible() {
+                ScreenController::watch(&mut display, &mut compositor, &mut step_count, &mut targets, &game, outcome);
+                ClockViewController::watch(&mut display, &game, outcome);
+                PedometerViewController::watch(&mut display, &mut step_count, &game, outcome);
+                ProgressViewController::watch(&mut display, &game, outcome);
+                PageController::watch(&mut display, &mut compositor, &mut targets, &game, outcome);
+            }
+        }
 
-    // 的の初期化
-    let mut target = Target::new();
+        // この周回で起きた出来事を音とセーブに知らせる
+        let events: Vec<Event, U32> = game.events.drain().collect();
+        SoundController::watch(&mut buzzer, &mut delay, &events);
+        // セーブできなかったら低い音で知らせる
+        if SaveController::watch(&mut storage, &mut autosave, &game, &events).is_err() {
+            beep(&mut buzzer, &mut delay, 220.hz(), 300u16);
+        }
 
-    // 初期画面の描画
-    screen::clear_screen(&mut display).unwrap();
-    NavigationView::render(&mut display, navigation.focus).unwrap();
-    PedometerView::render(&mut display, &mut pedometer.step_count).unwrap();
-    HomePage::render(&mut display).unwrap();
+        // 画面の電源の状態と明るさの設定にバックライトを合わせる
+        backlight.set(game.power.backlight(game.settings.brightness));
 
-    loop {
-        NavigationViewController::watch(
-            &mut display,
-            &mut buzzer,
-            &mut delay,
-            &switch_y,
-            &switch_b,
-            &switch_z,
-            &mut navigation,
-            &mut router,
-            &bet,
-            &meal,
-            &rice_ball,
-            &shuriken,
-        );
-
-        PedometerViewController::watch(
-            &mut display,
-            accel.accel_norm().unwrap(),
-            &mut pedometer,
-            &mut rice_ball,
-            &mut shuriken,
-        );
-
-        PageController::watch(
-            &mut display,
-            &mut buzzer,
-            &mut delay,
-            &switch_x,
-            &switch_u,
-            &switch_z,
-            &mut navigation,
-            &mut router,
-            &mut character,
-            &mut bet,
-            &mut meal,
-            &mut rice_ball,
-            &mut shuriken,
-            &mut target,
-        );
-
-        delay.delay_ms(100u16);
+        // 画面が点いている間は一定の間隔で周り、消えている間は加速度センサかボタンで起きるまで眠る
+        let wait = game.power.wait(LOOP_INTERVAL_MS);
+        let timestamp_ms = sampler.timestamp_ms();
+        match wait {
+            Wait::Interval(interval_ms) => sleeper.idle(interval_ms),
+            Wait::Standby => {
+                display.wait();
+                sleeper.standby();
+            }
+        }
+        elapsed_ms = wait.elapsed_ms(sampler.timestamp_ms().wrapping_sub(timestamp_ms));
     }
 }
@@ -0,0 +1,65 @@
+use models::input::InputEvent;
+
+// シミュレータに与える操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    // ボタン入力
+    Input(InputEvent),
+    // メインループ1回分の時間を進める
+    Tick,
+    // 1歩分の加速度を与える
+    Step,
+    // 時計を1時間進める
+    Hour,
+}
+
+impl Command {
+    // キー入力を操作に変換する。X/Y/Z/U/BはWio Terminalのスイッチに対応する
+    pub fn from_key(key: char) -> Option<Command> {
+        match key.to_ascii_lowercase() {
+            'x' => Some(Command::Input(InputEvent::Down)),
+            'y' => Some(Command::Input(InputEvent::Right)),
+            'z' => Some(Command::Input(InputEvent::Press)),
+            'u' => Some(Command::Input(InputEvent::Up)),
+            'b' => Some(Command::Input(InputEvent::Left)),
+            't' => Some(Command::Tick),
+            's' => Some(Command::Step),
+            'h' => Some(Command::Hour),
+            _ => None,
+        }
+    }
+    // 空白を読み飛ばしながらスクリプトを操作の並びに変換する
+    pub fn parse(script: &str) -> Result<Vec<Command>, char> {
+        script
+            .chars()
+            .filter(|key| !key.is_whitespace())
+            .map(|key| Command::from_key(key).ok_or(key))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let commands = Command::parse("yZ t\nsh").unwrap();
+
+        assert_eq!(
+            commands,
+            vec![
+                Command::Input(InputEvent::Right),
+                Command::Input(InputEvent::Press),
+                Command::Tick,
+                Command::Step,
+                Command::Hour,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_key() {
+        assert_eq!(Command::parse("yq"), Err('q'));
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    convert::Infallible,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use embedded_graphics::{
+    pixelcolor::{raw::RawU16, Rgb565, RgbColor},
+    prelude::*,
+    primitives::Rectangle,
+};
+use views::helpers::{
+    compositor::FlushTarget,
+    screen,
+};
+
+// Wio Terminalの画面と同じ320x240のRgb565フレームバッファ
+pub struct Framebuffer {
+    pixels: Vec<Rgb565>,
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Framebuffer::new()
+    }
+}
+
+impl Framebuffer {
+    pub const WIDTH: usize = screen::SCREEN_WIDTH as usize;
+    pub const HEIGHT: usize = screen::SCREEN_HEIGHT as usize;
+
+    pub fn new() -> Framebuffer {
+        Framebuffer {
+            pixels: vec![Rgb565::BLACK; Self::WIDTH * Self::HEIGHT],
+        }
+    }
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb565 {
+        self.pixels[y * Self::WIDTH + x]
+    }
+    // 8bitのRGBに変換した画素の並び
+    pub fn to_rgb888(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.pixels.len() * 3);
+
+        for color in &self.pixels {
+            let r = color.r();
+            let g = color.g();
+            let b = color.b();
+            data.push((r << 3) | (r >> 2));
+            data.push((g << 2) | (g >> 4));
+            data.push((b << 3) | (b >> 2));
+        }
+
+        data
+    }
+    pub fn write_ppm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", Self::WIDTH, Self::HEIGHT)?;
+        writer.write_all(&self.to_rgb888())
+    }
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, Self::WIDTH as u32, Self::HEIGHT as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.to_rgb888()))
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+    // 拡張子が.pngならPNG、それ以外はPPMで書き出す
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("png") => self.write_png(writer),
+            _ => self.write_ppm(&mut writer),
+        }
+    }
+}
+
+impl DrawTarget<Rgb565> for Framebuffer {
+    type Error = Infallible;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Self::Error> {
+        let Pixel(point, color) = pixel;
+
+        // 画面外の画素は捨てる
+        if point.x >= 0
+            && point.y >= 0
+            && (point.x as usize) < Self::WIDTH
+            && (point.y as usize) < Self::HEIGHT
+        {
+            self.pixels[point.y as usize * Self::WIDTH + point.x as usize] = color;
+        }
+
+        Ok(())
+    }
+    fn size(&self) -> Size {
+        Size::new(Self::WIDTH as u32, Self::HEIGHT as u32)
+    }
+}
+
+// 実機のDMA転送の代わりに、送られた帯をそのまま書き込む
+impl FlushTarget for Framebuffer {
+    type Error = Infallible;
+
+    fn flush(&mut self, area: &Rectangle, pixels: &mut [u16]) -> Result<(), Self::Error> {
+        let width = (area.bottom_right.x - area.top_left.x + 1) as usize;
+
+        for (index, raw) in pixels.iter().enumerate() {
+            let point = area.top_left + Point::new((index % width) as i32, (index / width) as i32);
+            self.draw_pixel(Pixel(point, Rgb565::from(RawU16::new(*raw))))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_pixel() {
+        let mut framebuffer = Framebuffer::new();
+
+        framebuffer.draw_pixel(Pixel(Point::new(1, 2), Rgb565::WHITE)).unwrap();
+        framebuffer.draw_pixel(Pixel(Point::new(-1, 2), Rgb565::WHITE)).unwrap();
+        framebuffer.draw_pixel(Pixel(Point::new(320, 2), Rgb565::WHITE)).unwrap();
+
+        assert_eq!(framebuffer.pixel(1, 2), Rgb565::WHITE);
+        assert_eq!(framebuffer.pixel(0, 2), Rgb565::BLACK);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.draw_pixel(Pixel(Point::new(0, 0), Rgb565::WHITE)).unwrap();
+
+        let mut data = Vec::new();
+        framebuffer.write_ppm(&mut data).unwrap();
+
+        let header = b"P6\n320 240\n255\n";
+        assert_eq!(&data[..header.len()], header);
+        assert_eq!(&data[header.len()..header.len() + 6], &[255, 255, 255, 0, 0, 0]);
+        assert_eq!(data.len(), header.len() + 320 * 240 * 3);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod command;
+pub mod framebuffer;
+pub mod simulator;
+pub mod snapshot;
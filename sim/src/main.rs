@@ -0,0 +1,128 @@
+use std::{
+    env,
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process,
+};
+
+use pocket_sim::{
+    command::Command,
+    framebuffer::Framebuffer,
+    simulator::Simulator,
+};
+
+const USAGE: &str = "\
+usage: pocket-sim [--out DIR] [--format png|ppm] [--script KEYS | --script-file FILE]
+
+keys: x/y/z/u/b = Wio Terminal switches, t = tick, s = step, h = hour
+without a script, keys are read from stdin line by line";
+
+struct Options {
+    out: PathBuf,
+    format: String,
+    script: Option<String>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        out: PathBuf::from("frames"),
+        format: String::from("png"),
+        script: None,
+    };
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+        match arg.as_str() {
+            "--out" => options.out = PathBuf::from(value()?),
+            "--format" => {
+                options.format = value()?;
+                if options.format != "png" && options.format != "ppm" {
+                    return Err(format!("unknown format {}", options.format));
+                }
+            }
+            "--script" => options.script = Some(value()?),
+            "--script-file" => {
+                let path = value()?;
+                let script = fs::read_to_string(&path).map_err(|error| format!("{}: {}", path, error))?;
+                options.script = Some(script);
+            }
+            "--help" | "-h" => return Err(String::new()),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+struct FrameWriter {
+    out: PathBuf,
+    format: String,
+    count: usize,
+}
+
+impl FrameWriter {
+    fn write(&mut self, display: &Framebuffer) {
+        let path = self.out.join(format!("frame_{:04}.{}", self.count, self.format));
+
+        if let Err(error) = display.save(&path) {
+            eprintln!("{}: {}", path.display(), error);
+            process::exit(1);
+        }
+
+        println!("{}", path.display());
+        self.count += 1;
+    }
+}
+
+fn run(simulator: &mut Simulator, writer: &mut FrameWriter, keys: &str) -> Result<(), char> {
+    for command in Command::parse(keys)? {
+        simulator.apply(command, |display| writer.write(display));
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|error| {
+        if !error.is_empty() {
+            eprintln!("{}", error);
+        }
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+
+    if let Err(error) = fs::create_dir_all(&options.out) {
+        eprintln!("{}: {}", options.out.display(), error);
+        process::exit(1);
+    }
+
+    let mut simulator = Simulator::new();
+    let mut writer = FrameWriter {
+        out: options.out,
+        format: options.format,
+        count: 0,
+    };
+    writer.write(&simulator.display);
+
+    match options.script {
+        Some(script) => {
+            if let Err(key) = run(&mut simulator, &mut writer, &script) {
+                eprintln!("unknown key {:?}", key);
+                process::exit(2);
+            }
+        }
+        None => {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                let line = line.unwrap_or_default();
+                if let Err(key) = run(&mut simulator, &mut writer, &line) {
+                    eprintln!("unknown key {:?}", key);
+                }
+                io::stdout().flush().ok();
+            }
+        }
+    }
+}
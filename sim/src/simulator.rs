@@ -0,0 +1,455 @@
+use crate::{
+    command::Command,
+    framebuffer::Framebuffer,
+};
+
+use accelerometer::vector::F32x3;
+use models::{
+    clock::{
+        Clock,
+        FakeClock,
+    },
+    event::Event,
+    game::{
+        Game,
+        Outcome,
+    },
+    pedometer::Sample,
+    router::Route,
+    scene::Scene,
+    target::TargetPosition,
+};
+use views::{
+    clock_view::ClockView,
+    helpers::{
+        dirty::DirtyTracker,
+        image,
+        screen,
+        sprite::AnimatedSprite,
+        theme,
+    },
+    navigation_view::NavigationView,
+    pedometer_view::PedometerView,
+    progress_view::ProgressView,
+    pages::{
+        eat_page::EatPage,
+        fail_page::FailPage,
+        game_page::GamePage,
+        home_page::HomePage,
+        meal_page::MealPage,
+        play_page::PlayPage,
+        stats_page::{
+            StatsModel,
+            StatsPage,
+        },
+        success_page::SuccessPage,
+        throw_page::ThrowPage,
+    },
+};
+
+// 実機のメインループの間隔(ミリ秒)
+pub const LOOP_INTERVAL_MS: u32 = 100;
+const HOUR_MS: u32 = 60 * 60 * 1000;
+
+// 実機のコントローラーと同じ順番でビューを描画するシミュレータ
+pub struct Simulator {
+    pub game: Game,
+    pub clock: FakeClock,
+    pub display: Framebuffer,
+    // Homeで待機中のキャラクターのアニメーション
+    pub sprite: AnimatedSprite,
+    // 実機と同じく、変わったところだけを描き直すために前に描いた値を覚えておく
+    pub step_count: DirtyTracker<i32>,
+    pub targets: DirtyTracker<TargetPosition>,
+    // 実機では音とセーブが受け取る出来事を、起きた順に残しておく
+    pub events: Vec<Event>,
+    // 実機のバックライトの明るさ。0なら消えている
+    pub backlight: i32,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Simulator::new()
+    }
+}
+
+impl Simulator {
+    pub fn new() -> Simulator {
+        let game = Game::new();
+        let sprite = AnimatedSprite::new(image::get_character_frames(&image::get_mood_state(&game.mood())));
+        let mut simulator = Simulator {
+            game,
+            clock: FakeClock::default(),
+            display: Framebuffer::new(),
+            sprite,
+            step_count: DirtyTracker::new(),
+            targets: DirtyTracker::new(),
+            events: Vec::new(),
+            backlight: 0,
+        };
+        simulator.backlight = simulator.game.power.backlight(simulator.game.settings.brightness);
+
+        // 初期画面の描画
+        simulator.render_screen();
+
+        simulator
+    }
+    // 操作を実行し、画面が変わるたびにon_frameを呼ぶ
+    pub fn apply<F>(&mut self, command: Command, mut on_frame: F)
+    where
+        F: FnMut(&Framebuffer),
+    {
+        // 実機と同じく、画面が消えている間は描画を省き、点いたときにまとめて描き直す
+        let was_visible = self.game.power.is_visible();
+
+        let outcome = match command {
+            Command::Input(event) => {
+                let outcome = self.game.handle(event);
+                // 実機と同じく、時計を合わせたら次に読む前に時計も合わせる
+                if outcome == Some(Outcome::TimeSet) {
+                    self.clock.set(self.game.time);
+                }
+                outcome
+            }
+            Command::Tick => {
+                self.clock.advance(LOOP_INTERVAL_MS);
+                self.game.tick(LOOP_INTERVAL_MS)
+            }
+            Command::Step => {
+                // 踏み込んで戻したところで1歩になる。続けて歩いても1歩の最小の間隔より空ける
+                let clock = &mut self.clock;
+                let samples = [1.0, 2.0, 1.0].iter().map(|z| {
+                    clock.advance(LOOP_INTERVAL_MS);
+                    Sample {
+                        timestamp_ms: clock.milliseconds as u32,
+                        accel: F32x3::new(0.0, 0.0, *z),
+                    }
+                });
+                self.game.sample(samples)
+            }
+            Command::Hour => {
+                self.clock.advance(HOUR_MS);
+                None
+            }
+        };
+
+        if let Some(outcome) = outcome {
+            if was_visible && self.game.power.is_visible() {
+                self.render(outcome, &mut on_frame);
+            }
+        }
+        if !was_visible && self.game.power.is_visible() {
+            self.render_screen();
+            on_frame(&self.display);
+        }
+        if command == Command::Tick && self.game.power.is_visible() {
+            self.animate(&mut on_frame);
+        }
+
+        // 実機と同じく毎回時計を読む
+        if let Some(outcome) = self.game.update_time(self.clock.now()) {
+            if self.game.power.is_visible() {
+                self.render(outcome, &mut on_frame);
+            }
+        }
+
+        self.events.extend(self.game.events.drain());
+
+        // 実機と同じく、毎回バックライトを画面の電源の状態に合わせる
+        self.backlight = self.game.power.backlight(self.game.settings.brightness);
+    }
+    // 実機のHomePageController::animateと同じく、Homeが見えている間だけアニメーションを進める
+    fn animate<F>(&mut self, on_frame: &mut F)
+    where
+        F: FnMut(&Framebuffer),
+    {
+        if !self.game.is_home_visible() {
+            self.sprite.reset();
+            return;
+        }
+
+        let mood = self.game.mood();
+        self.sprite.play(image::get_character_frames(&image::get_mood_state(&mood)));
+        if self.sprite.tick(LOOP_INTERVAL_MS) {
+            let theme = theme::get_theme(self.game.dark);
+            HomePage::render_sprite(&mut self.display, theme, &mood, &self.sprite).unwrap();
+            on_frame(&self.display);
+        }
+    }
+    fn render<F>(&mut self, outcome: Outcome, on_frame: &mut F)
+    where
+        F: FnMut(&Framebuffer),
+    {
+        let display = &mut self.display;
+        let game = &self.game;
+        let theme = theme::get_theme(game.dark);
+
+        match outcome {
+            Outcome::Focused(focus) => {
+                NavigationView::render(display, theme, focus).unwrap();
+            }
+            Outcome::Routed(Route::Home) => {
+                HomePage::render(display, theme, &game.mood()).unwrap();
+            }
+            Outcome::MoodChanged => {
+                ClockView::render(display, theme, &game.time).unwrap();
+                HomePage::render(display, theme, &game.mood()).unwrap();
+            }
+            Outcome::MinutePassed => {
+                ClockView::render(display, theme, &game.time).unwrap();
+            }
+            Outcome::DayChanged => {
+                ClockView::render(display, theme, &game.time).unwrap();
+                PedometerView::render_dirty(display, theme, &mut self.step_count, &game.daily_step_count()).unwrap();
+                ProgressView::render(display, theme, &game.progress()).unwrap();
+                if game.router.route == Route::Stats {
+                    StatsPage::render(display, theme, &StatsModel::from_game(game)).unwrap();
+                }
+            }
+            Outcome::Routed(Route::Meal) | Outcome::MealChanged => {
+                MealPage::render(display, theme, &game.rice_ball, &game.meal).unwrap();
+            }
+            Outcome::Routed(Route::Play) | Outcome::BetChanged => {
+                PlayPage::render(display, theme, &game.bet, &game.shuriken).unwrap();
+            }
+            Outcome::GoalChanged => {
+                ProgressView::render(display, theme, &game.progress()).unwrap();
+                StatsPage::render(display, theme, &StatsModel::from_game(game)).unwrap();
+            }
+            Outcome::Routed(Route::Stats) | Outcome::SettingsChanged => {
+                StatsPage::render(display, theme, &StatsModel::from_game(game)).unwrap();
+            }
+            Outcome::Routed(Route::Game) | Outcome::TargetMoved => {
+                if outcome == Outcome::Routed(Route::Game) {
+                    self.targets.invalidate();
+                }
+                GamePage::render_dirty(display, theme, &mut self.targets, &game.target.position).unwrap();
+            }
+            Outcome::Ate | Outcome::GameWon | Outcome::GameLost | Outcome::SceneChanged => {
+                let frame = game.scenes.frame();
+                match game.scenes.current() {
+                    Some(Scene::Throw) => ThrowPage::render(display, theme, frame).unwrap(),
+                    Some(Scene::Eat) if frame == 0 => EatPage::render(display, theme).unwrap(),
+                    Some(Scene::Success) if frame == 0 => SuccessPage::render(display, theme).unwrap(),
+                    Some(Scene::Fail) if frame == 0 => FailPage::render(display, theme).unwrap(),
+                    _ => {}
+                }
+            }
+            Outcome::SceneEnded => {
+                NavigationView::render(display, theme, game.navigation.focus).unwrap();
+                HomePage::render(display, theme, &game.mood()).unwrap();
+            }
+            Outcome::Walked | Outcome::GoalReached | Outcome::ActivityChanged => {
+                PedometerView::render_dirty(display, theme, &mut self.step_count, &game.daily_step_count()).unwrap();
+                ProgressView::render(display, theme, &game.progress()).unwrap();
+                if outcome == Outcome::ActivityChanged && game.is_home_visible() {
+                    HomePage::render(display, theme, &game.mood()).unwrap();
+                }
+                if game.router.route == Route::Stats {
+                    StatsPage::render(display, theme, &StatsModel::from_game(game)).unwrap();
+                }
+            }
+            Outcome::ThemeChanged | Outcome::TimeSet => {
+                self.render_screen();
+            }
+            Outcome::Touched => return,
+        }
+
+        on_frame(&self.display);
+    }
+    // 画面全体を今の色で描き直す
+    fn render_screen(&mut self) {
+        let display = &mut self.display;
+        let game = &self.game;
+        let theme = theme::get_theme(game.dark);
+
+        screen::clear_screen(display, theme).unwrap();
+        NavigationView::render(display, theme, game.navigation.focus).unwrap();
+        ClockView::render(display, theme, &game.time).unwrap();
+        self.step_count.invalidate();
+        PedometerView::render_dirty(display, theme, &mut self.step_count, &game.daily_step_count()).unwrap();
+        ProgressView::render(display, theme, &game.progress()).unwrap();
+
+        match game.router.route {
+            Route::Home => HomePage::render(display, theme, &game.mood()).unwrap(),
+            Route::Meal => MealPage::render(display, theme, &game.rice_ball, &game.meal).unwrap(),
+            Route::Play => PlayPage::render(display, theme, &game.bet, &game.shuriken).unwrap(),
+            Route::Game => {
+                self.targets.invalidate();
+                GamePage::render_dirty(display, theme, &mut self.targets, &game.target.position).unwrap();
+            }
+            Route::Stats => StatsPage::render(display, theme, &StatsModel::from_game(game)).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use models::{
+        input::InputEvent,
+        needs::Mood,
+        power::OFF_AFTER_MS,
+        settings::MAX_BRIGHTNESS,
+    };
+
+    #[test]
+    fn test_apply() {
+        let mut simulator = Simulator::new();
+        let mut frames = 0;
+
+        simulator.apply(Command::Input(InputEvent::Right), |_| frames += 1);
+        simulator.apply(Command::Input(InputEvent::Press), |_| frames += 1);
+        simulator.apply(Command::Input(InputEvent::Press), |_| frames += 1);
+
+        assert_eq!(simulator.game.router.route, Route::Meal);
+        assert_eq!(frames, 2);
+    }
+
+    #[test]
+    fn test_apply_game_session() {
+        let mut simulator = Simulator::new();
+        simulator.game.shuriken.amount = 1;
+        let mut frames = 0;
+
+        // 投擲と失敗の演出はボタンで飛ばす
+        for command in Command::parse("yyzuzzzz").unwrap() {
+            simulator.apply(command, |_| frames += 1);
+        }
+
+        // ナビ2回、Play、BET、Game、投擲、失敗、Home
+        assert_eq!(frames, 8);
+        assert!(!simulator.game.scenes.is_playing());
+        assert_eq!(simulator.game.router.route, Route::Home);
+        assert_eq!(
+            simulator.events,
+            [
+                Event::RouteChanged(Route::Play),
+                Event::RouteChanged(Route::Game),
+                Event::GameLost,
+                Event::RouteChanged(Route::Home),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_night() {
+        let mut simulator = Simulator::new();
+        let mut frames = 0;
+
+        for command in Command::parse("hhhhhhhhh").unwrap() {
+            simulator.apply(command, |_| frames += 1);
+        }
+
+        // 21時までは時計だけが描き変わる
+        assert_eq!(frames, 9);
+        assert_eq!(simulator.game.mood(), Mood::Away);
+
+        simulator.apply(Command::Hour, |_| frames += 1);
+
+        assert_eq!(simulator.game.time.hour, 22);
+        assert_eq!(simulator.game.mood(), Mood::Sleep);
+
+        // 夜は画面全体が暗い色で描き直される
+        assert!(simulator.game.dark);
+        assert_eq!(simulator.display.pixel(0, Framebuffer::HEIGHT - 1), theme::DARK.background);
+        assert_eq!(simulator.display.pixel(Framebuffer::WIDTH - 1, 0), theme::DARK.background);
+    }
+
+    #[test]
+    fn test_apply_set_time() {
+        let mut simulator = Simulator::new();
+        let mut frames = 0;
+
+        // Statsで時を選んで1時間戻す
+        for event in [InputEvent::Right, InputEvent::Right, InputEvent::Right, InputEvent::Press] {
+            simulator.apply(Command::Input(event), |_| {});
+        }
+        for _ in 0..2 {
+            simulator.apply(Command::Input(InputEvent::Press), |_| {});
+        }
+        simulator.apply(Command::Input(InputEvent::Down), |_| frames += 1);
+
+        assert_eq!(frames, 1);
+        assert_eq!(simulator.game.time.hour, 11);
+
+        // 時計も合わせたので、読み直しても戻らない
+        simulator.apply(Command::Tick, |_| {});
+
+        assert_eq!(simulator.clock.now().hour, 11);
+        assert_eq!(simulator.game.time.hour, 11);
+    }
+
+    #[test]
+    fn test_apply_step() {
+        let mut simulator = Simulator::new();
+        let mut frames = 0;
+
+        // 実機と同じく、5歩続けて歩き始めたと分かるまでは数えずに、5歩目でまとめて数える
+        for command in Command::parse("sssss").unwrap() {
+            simulator.apply(command, |_| frames += 1);
+        }
+
+        assert_eq!(simulator.game.daily_step_count(), 5);
+        assert_eq!(frames, 1);
+
+        simulator.apply(Command::Step, |_| frames += 1);
+
+        assert_eq!(simulator.game.daily_step_count(), 6);
+        assert_eq!(frames, 2);
+    }
+
+    #[test]
+    fn test_apply_power_timeout() {
+        let mut simulator = Simulator::new();
+        let mut frames = 0;
+
+        assert_eq!(simulator.backlight, MAX_BRIGHTNESS);
+
+        for _ in 0..OFF_AFTER_MS / LOOP_INTERVAL_MS {
+            simulator.apply(Command::Tick, |_| {});
+        }
+
+        assert_eq!(simulator.backlight, 0);
+
+        // 消えている間は時間が経っても描かない
+        simulator.apply(Command::Tick, |_| frames += 1);
+        simulator.apply(Command::Hour, |_| frames += 1);
+
+        assert_eq!(frames, 0);
+
+        // 最初のボタンは画面を点けるだけで、ナビゲーションは動かさない。消えている間の分は画面全体を1回で描き直す
+        simulator.apply(Command::Input(InputEvent::Right), |_| frames += 1);
+
+        assert_eq!(simulator.backlight, MAX_BRIGHTNESS);
+        assert_eq!(simulator.game.navigation.focus, Route::Home);
+        assert_eq!(frames, 1);
+    }
+
+    #[test]
+    fn test_apply_idle_animation() {
+        let mut simulator = Simulator::new();
+        let mut frames = 0;
+
+        // 離れているときは1.5秒ごとに息をする
+        for _ in 0..30 {
+            simulator.apply(Command::Tick, |_| frames += 1);
+        }
+
+        assert_eq!(simulator.game.mood(), Mood::Away);
+        assert_eq!(frames, 2);
+        assert_eq!(simulator.sprite.index, 0);
+
+        // Home以外ではアニメーションしない
+        simulator.apply(Command::Input(InputEvent::Right), |_| {});
+        simulator.apply(Command::Input(InputEvent::Press), |_| {});
+        frames = 0;
+        for _ in 0..30 {
+            simulator.apply(Command::Tick, |_| frames += 1);
+        }
+
+        assert_eq!(simulator.game.router.route, Route::Meal);
+        assert_eq!(frames, 0);
+    }
+}
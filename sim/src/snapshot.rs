@@ -0,0 +1,527 @@
+use crate::framebuffer::Framebuffer;
+
+use std::{
+    env,
+    fs::{self, File},
+    io,
+    path::PathBuf,
+};
+
+// 基準画像を置くディレクトリ
+pub fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+// PNGを読み込んで8bitのRGBの並びを返す
+pub fn load_png(path: &PathBuf) -> io::Result<Vec<u8>> {
+    let decoder = png::Decoder::new(File::open(path)?);
+    let (info, mut reader) = decoder
+        .read_info()
+        .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+    let mut data = vec![0; info.buffer_size()];
+
+    reader.next_frame(&mut data).map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+
+    Ok(data)
+}
+
+// 描画結果を基準画像と比較する。
+// UPDATE_SNAPSHOTSが設定されているときは基準画像を書き換える。
+// 一致しないときは<name>.actual.pngを書き出してpanicする。
+pub fn assert_snapshot(name: &str, display: &Framebuffer) {
+    let dir = snapshot_dir();
+    let expected_path = dir.join(format!("{}.png", name));
+    let actual_path = dir.join(format!("{}.actual.png", name));
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(&dir).unwrap();
+        display.save(&expected_path).unwrap();
+        fs::remove_file(&actual_path).ok();
+        return;
+    }
+
+    let expected = load_png(&expected_path).unwrap_or_else(|error| {
+        panic!("{}: {} (run with UPDATE_SNAPSHOTS=1 to create it)", expected_path.display(), error)
+    });
+
+    if expected != display.to_rgb888() {
+        display.save(&actual_path).unwrap();
+        panic!(
+            "snapshot {} does not match, see {}",
+            name,
+            actual_path.display()
+        );
+    }
+
+    fs::remove_file(&actual_path).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::{
+        pixelcolor::{Rgb565, RgbColor},
+        prelude::*,
+    };
+    use models::{
+        activity::{
+            Activity,
+            ActivityClassifier,
+        },
+        bet::Bet,
+        clock::DateTime,
+        goal::DEFAULT_GOAL_STEP_COUNT,
+        meal::Meal,
+        profile::{
+            Exercise,
+            Profile,
+        },
+        needs::Mood,
+        rice_ball::RiceBall,
+        router::Route,
+        settings::{
+            SettingItem,
+            Settings,
+            ThemeMode,
+        },
+        shuriken::Shuriken,
+        target::TargetPosition,
+    };
+    use views::{
+        clock_view::ClockView,
+        helpers::{
+            asset,
+            compositor::Compositor,
+            dirty::DirtyTracker,
+            image,
+            screen,
+            sprite::AnimatedSprite,
+            theme,
+        },
+        navigation_view::NavigationView,
+        pedometer_view::PedometerView,
+        progress_view::ProgressView,
+        pages::{
+            eat_page::EatPage,
+            fail_page::FailPage,
+            game_page::GamePage,
+            home_page::HomePage,
+            meal_page::MealPage,
+            play_page::PlayPage,
+            stats_page::{
+                StatsModel,
+                StatsPage,
+            },
+            success_page::SuccessPage,
+            throw_page::ThrowPage,
+        },
+    };
+
+    // 黒で塗られたフレームバッファに描画して、描画範囲も分かるようにする
+    fn render<F>(draw: F) -> Framebuffer
+    where
+        F: FnOnce(&mut Framebuffer),
+    {
+        let mut display = Framebuffer::new();
+        draw(&mut display);
+        display
+    }
+
+    // 指定した範囲の外に描画されていないか
+    fn is_drawn_only_within(display: &Framebuffer, top_left: Point, bottom_right: Point) -> bool {
+        (0..Framebuffer::HEIGHT).all(|y| {
+            (0..Framebuffer::WIDTH).all(|x| {
+                let inside = x as i32 >= top_left.x
+                    && x as i32 <= bottom_right.x
+                    && y as i32 >= top_left.y
+                    && y as i32 <= bottom_right.y;
+                inside || display.pixel(x, y) == Rgb565::BLACK
+            })
+        })
+    }
+
+    #[test]
+    fn test_eat_page() {
+        let display = render(|display| EatPage::render(display, &theme::LIGHT).unwrap());
+
+        assert_snapshot("eat_page", &display);
+    }
+
+    #[test]
+    fn test_fail_page() {
+        let display = render(|display| FailPage::render(display, &theme::LIGHT).unwrap());
+
+        assert_snapshot("fail_page", &display);
+    }
+
+    #[test]
+    fn test_success_page() {
+        let display = render(|display| SuccessPage::render(display, &theme::LIGHT).unwrap());
+
+        assert_snapshot("success_page", &display);
+    }
+
+    #[test]
+    fn test_throw_page() {
+        let display = render(|display| ThrowPage::render(display, &theme::LIGHT, 0).unwrap());
+
+        assert_snapshot("throw_page", &display);
+    }
+
+    #[test]
+    fn test_throw_page_frame() {
+        let display = render(|display| ThrowPage::render(display, &theme::LIGHT, 3).unwrap());
+
+        // 前のコマの手裏剣を消して、次の位置に描く以外は描き変えない
+        assert!(is_drawn_only_within(&display, Point::new(212, 100), Point::new(259, 131)));
+        assert_snapshot("throw_page_frame", &display);
+    }
+
+    #[test]
+    fn test_home_page() {
+        for (mood, name) in [
+            (Mood::Angry, "home_page_angry"),
+            (Mood::Away, "home_page_away"),
+            (Mood::Happy, "home_page_happy"),
+            (Mood::Shy, "home_page_shy"),
+            (Mood::Sleep, "home_page_sleep"),
+        ] {
+            let display = render(|display| HomePage::render(display, &theme::LIGHT, &mood).unwrap());
+
+            assert_snapshot(name, &display);
+        }
+    }
+
+    #[test]
+    fn test_layers() {
+        use embedded_graphics::{egrectangle, primitive_style};
+        use views::helpers::{
+            asset,
+            image::{draw_layers, Layer},
+        };
+
+        let background = Rgb565::new(8, 40, 24);
+        let display = render(|display| {
+            egrectangle!(
+                top_left = (0, screen::STATUS_BAR_HEIGHT),
+                bottom_right = (screen::SCREEN_WIDTH - 1, screen::SCREEN_HEIGHT - 1),
+                style = primitive_style!(fill_color = background)
+            )
+            .draw(display)
+            .unwrap();
+            draw_layers(display, &[
+                Layer::new(asset::CHARACTER_HAPPY),
+                Layer::at(asset::GAME_SHURIKEN, Point::new(260, 150)),
+            ])
+            .unwrap();
+        });
+
+        // 画像の外側の白は透明になり、目の白は残る
+        assert_eq!(display.pixel(60, 90), background);
+        assert_eq!(display.pixel(150, 174), Rgb565::WHITE);
+        assert_snapshot("layers", &display);
+    }
+
+    #[test]
+    fn test_home_page_blink() {
+        let mood = Mood::Happy;
+        let mut sprite = AnimatedSprite::new(image::get_character_frames(&image::get_mood_state(&mood)));
+        sprite.index = 3;
+        let display = render(|display| {
+            HomePage::render(display, &theme::LIGHT, &mood).unwrap();
+            HomePage::render_sprite(display, &theme::LIGHT, &mood, &sprite).unwrap();
+        });
+
+        assert_snapshot("home_page_happy_blink", &display);
+    }
+
+    #[test]
+    fn test_home_page_breath() {
+        let mood = Mood::Happy;
+        let mut sprite = AnimatedSprite::new(image::get_character_frames(&image::get_mood_state(&mood)));
+        let mut display = render(|display| HomePage::render(display, &theme::LIGHT, &mood).unwrap());
+        let expected = render(|display| HomePage::render(display, &theme::LIGHT, &mood).unwrap());
+
+        // 息を吸って吐いたら、最初のコマと同じ画面に戻る
+        for index in 1..=2 {
+            sprite.index = index;
+            HomePage::render_sprite(&mut display, &theme::LIGHT, &mood, &sprite).unwrap();
+        }
+
+        assert!((0..Framebuffer::HEIGHT).all(|y| {
+            (0..Framebuffer::WIDTH).all(|x| display.pixel(x, y) == expected.pixel(x, y))
+        }));
+    }
+
+    #[test]
+    fn test_meal_page() {
+        let rice_ball = RiceBall { amount: 12, last_step_count: 0 };
+        let meal = Meal { amount: 3 };
+        let display = render(|display| MealPage::render(display, &theme::LIGHT, &rice_ball, &meal).unwrap());
+
+        assert_snapshot("meal_page", &display);
+    }
+
+    #[test]
+    fn test_play_page() {
+        let bet = Bet { amount: 4 };
+        let shuriken = Shuriken { amount: 7, last_step_count: 0 };
+        let display = render(|display| PlayPage::render(display, &theme::LIGHT, &bet, &shuriken).unwrap());
+
+        assert_snapshot("play_page", &display);
+    }
+
+    #[test]
+    fn test_game_page() {
+        for (position, name) in [
+            (TargetPosition::Left, "game_page_left"),
+            (TargetPosition::Center, "game_page_center"),
+            (TargetPosition::Right, "game_page_right"),
+        ] {
+            let display = render(|display| GamePage::render(display, &theme::LIGHT, &position).unwrap());
+
+            assert_snapshot(name, &display);
+        }
+    }
+
+    #[test]
+    fn test_game_page_dirty() {
+        let mut tracker = DirtyTracker { drawn: Some(TargetPosition::Left) };
+        let display = render(|display| {
+            GamePage::render_dirty(display, &theme::LIGHT, &mut tracker, &TargetPosition::Center).unwrap()
+        });
+        let cell = asset::GAME_TARGET.size();
+
+        // 手裏剣が移った左と中央の的だけを描く
+        assert!(is_drawn_only_within(
+            &display,
+            Point::new(0, screen::STATUS_BAR_HEIGHT),
+            Point::new(cell.width as i32 * 2 - 1, screen::STATUS_BAR_HEIGHT + cell.height as i32 - 1),
+        ));
+        assert_ne!(display.pixel(0, screen::STATUS_BAR_HEIGHT as usize), Rgb565::BLACK);
+
+        // 描き直した的は全体を描いたときと同じになる
+        let expected = render(|display| GamePage::render(display, &theme::LIGHT, &TargetPosition::Center).unwrap());
+        assert!((0..cell.width as usize * 2).all(|x| {
+            (screen::STATUS_BAR_HEIGHT as usize..(screen::STATUS_BAR_HEIGHT as u32 + cell.height) as usize)
+                .all(|y| display.pixel(x, y) == expected.pixel(x, y))
+        }));
+    }
+
+    #[test]
+    fn test_compose_page() {
+        // 実機と同じく16行ずつの帯に分けて描く
+        let mut front = [0; Framebuffer::WIDTH * 16];
+        let mut back = [0; Framebuffer::WIDTH * 16];
+        let mut compositor = Compositor::new(&mut front, &mut back);
+        let mut display = Framebuffer::new();
+        compositor
+            .compose(&mut display, &screen::PAGE_AREA, theme::LIGHT.background, |tile| {
+                HomePage::render(tile, &theme::LIGHT, &Mood::Happy).unwrap();
+            })
+            .unwrap();
+
+        // 帯ごとに描いても、直接描いたときと同じになる
+        let expected = render(|display| HomePage::render(display, &theme::LIGHT, &Mood::Happy).unwrap());
+        assert!((0..Framebuffer::HEIGHT).all(|y| {
+            (0..Framebuffer::WIDTH).all(|x| display.pixel(x, y) == expected.pixel(x, y))
+        }));
+    }
+
+    #[test]
+    fn test_navigation_view() {
+        for (route, name) in [
+            (Route::Home, "navigation_view_home"),
+            (Route::Meal, "navigation_view_meal"),
+            (Route::Play, "navigation_view_play"),
+            (Route::Game, "navigation_view_game"),
+            (Route::Stats, "navigation_view_stats"),
+        ] {
+            let display = render(|display| NavigationView::render(display, &theme::LIGHT, route).unwrap());
+
+            assert_snapshot(name, &display);
+            assert!(is_drawn_only_within(
+                &display,
+                Point::new(0, 0),
+                Point::new(screen::CLOCK_LEFT - 1, screen::STATUS_BAR_HEIGHT - 1),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stats_page() {
+        let mut classifier = ActivityClassifier::new();
+        classifier.activity = Activity::Walking;
+        classifier.cadence = 108;
+        let stats = StatsModel {
+            days: [4210, 0, 8123, 12034, 650, 9999, 3021],
+            today: &DateTime { month: 3, day: 3, ..DateTime::default() },
+            classifier: &classifier,
+            exercise: &Exercise {
+                distance_cm: 211_460,
+                calories: 63_438,
+            },
+            settings: &Settings::new(),
+            goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+            profile: &Profile::default(),
+        };
+        let display = render(|display| StatsPage::render(display, &theme::LIGHT, &stats).unwrap());
+
+        assert_snapshot("stats_page", &display);
+        assert!(is_drawn_only_within(
+            &display,
+            Point::new(0, screen::STATUS_BAR_HEIGHT),
+            Point::new(screen::SCREEN_WIDTH - 1, screen::SCREEN_HEIGHT - 1),
+        ));
+    }
+
+    #[test]
+    fn test_stats_page_backlight() {
+        let mut settings = Settings::new();
+        settings.select_next();
+        settings.decrease();
+        let stats = StatsModel {
+            days: [0; 7],
+            today: &DateTime::default(),
+            classifier: &ActivityClassifier::new(),
+            exercise: &Exercise::new(),
+            settings: &settings,
+            goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+            profile: &Profile::default(),
+        };
+        let display = render(|display| StatsPage::render(display, &theme::LIGHT, &stats).unwrap());
+
+        assert_snapshot("stats_page_backlight", &display);
+    }
+
+    #[test]
+    fn test_stats_page_clock() {
+        let mut settings = Settings::new();
+        settings.selected = SettingItem::Minute;
+        let stats = StatsModel {
+            days: [0; 7],
+            today: &DateTime { hour: 7, minute: 5, ..DateTime::default() },
+            classifier: &ActivityClassifier::new(),
+            exercise: &Exercise::new(),
+            settings: &settings,
+            goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+            profile: &Profile::default(),
+        };
+        let display = render(|display| StatsPage::render(display, &theme::LIGHT, &stats).unwrap());
+
+        assert_snapshot("stats_page_clock", &display);
+    }
+
+    #[test]
+    fn test_stats_page_profile() {
+        let mut settings = Settings::new();
+        settings.selected = SettingItem::Stride;
+        let stats = StatsModel {
+            days: [0; 7],
+            today: &DateTime::default(),
+            classifier: &ActivityClassifier::new(),
+            exercise: &Exercise::new(),
+            settings: &settings,
+            goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+            profile: &Profile::new(160, 50, Some(65)),
+        };
+        let display = render(|display| StatsPage::render(display, &theme::LIGHT, &stats).unwrap());
+
+        assert_snapshot("stats_page_profile", &display);
+    }
+
+    #[test]
+    fn test_dark_theme() {
+        let mut settings = Settings::new();
+        settings.theme = ThemeMode::Dark;
+        let stats = StatsModel {
+            days: [0, 0, 0, 0, 0, 4210, 3021],
+            today: &DateTime::default(),
+            classifier: &ActivityClassifier::new(),
+            exercise: &Exercise::new(),
+            settings: &settings,
+            goal_step_count: DEFAULT_GOAL_STEP_COUNT,
+            profile: &Profile::default(),
+        };
+        let display = render(|display| {
+            let theme = &theme::DARK;
+            screen::clear_screen(display, theme).unwrap();
+            NavigationView::render(display, theme, Route::Stats).unwrap();
+            ClockView::render(display, theme, &DateTime { hour: 23, ..DateTime::default() }).unwrap();
+            PedometerView::render(display, theme, &3021).unwrap();
+            ProgressView::render(display, theme, &(3021, 5000)).unwrap();
+            StatsPage::render(display, theme, &stats).unwrap();
+        });
+
+        assert_snapshot("dark_theme", &display);
+    }
+
+    #[test]
+    fn test_pedometer_view() {
+        let display = render(|display| PedometerView::render(display, &theme::LIGHT, &1234).unwrap());
+
+        assert_snapshot("pedometer_view", &display);
+        assert!(is_drawn_only_within(
+            &display,
+            Point::new(screen::PEDOMETER_LEFT, 0),
+            Point::new(screen::SCREEN_WIDTH - 1, screen::STATUS_BAR_HEIGHT - 1),
+        ));
+    }
+
+    #[test]
+    fn test_pedometer_view_dirty() {
+        let mut tracker = DirtyTracker::new();
+        let first = render(|display| PedometerView::render_dirty(display, &theme::LIGHT, &mut tracker, &1234).unwrap());
+        let unchanged = render(|display| PedometerView::render_dirty(display, &theme::LIGHT, &mut tracker, &1234).unwrap());
+
+        assert_snapshot("pedometer_view", &first);
+        // 歩数が変わらなければ何も描かない
+        assert!(is_drawn_only_within(&unchanged, Point::zero(), Point::new(-1, -1)));
+    }
+
+    #[test]
+    fn test_progress_view() {
+        for (progress, name) in [
+            ((0, 3000), "progress_view_empty"),
+            ((1200, 3000), "progress_view_partial"),
+            ((1, 1), "progress_view_full"),
+        ] {
+            let display = render(|display| ProgressView::render(display, &theme::LIGHT, &progress).unwrap());
+
+            assert_snapshot(name, &display);
+            assert!(is_drawn_only_within(
+                &display,
+                Point::new(screen::CLOCK_LEFT, screen::PROGRESS_TOP),
+                Point::new(screen::PEDOMETER_LEFT - 1, screen::STATUS_BAR_HEIGHT - 1),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_clock_view() {
+        let time = DateTime { hour: 9, minute: 5, ..DateTime::default() };
+        let display = render(|display| ClockView::render(display, &theme::LIGHT, &time).unwrap());
+
+        assert_snapshot("clock_view", &display);
+        assert!(is_drawn_only_within(
+            &display,
+            Point::new(screen::CLOCK_LEFT, 0),
+            Point::new(screen::PEDOMETER_LEFT - 1, screen::PROGRESS_TOP - 1),
+        ));
+    }
+
+    #[test]
+    fn test_pages_do_not_draw_over_status_bar() {
+        let display = render(|display| {
+            HomePage::render(display, &theme::LIGHT, &Mood::Away).unwrap();
+            GamePage::render(display, &theme::LIGHT, &TargetPosition::Center).unwrap();
+        });
+
+        assert!(is_drawn_only_within(
+            &display,
+            Point::new(0, screen::STATUS_BAR_HEIGHT),
+            Point::new(screen::SCREEN_WIDTH - 1, screen::SCREEN_HEIGHT - 1),
+        ));
+    }
+}
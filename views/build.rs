@@ -0,0 +1,148 @@
+// src/assets/manifest.txtに書かれたPNGをRgb565に変換してhelpers::rleで圧縮し、
+// 幅と高さと描画位置を持つ定数のモジュールを生成する。
+// PNGの透明な画素は、画像の中で使われていない色(カラーキー)に置き換えておく
+#[allow(dead_code)]
+#[path = "src/helpers/rle.rs"]
+mod rle;
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+const ASSETS_DIR: &str = "src/assets";
+const MANIFEST: &str = "src/assets/manifest.txt";
+// 圧縮の形式が変わったら、描画時に展開する側と合うように作り直す
+const ENCODER: &str = "src/helpers/rle.rs";
+// カラーキーの候補の最初の色。使われていたら次の色を試す
+const PREFERRED_KEY: u16 = 0xF81F;
+// これより不透明度が低い画素を透明とみなす
+const ALPHA_THRESHOLD: u8 = 128;
+
+struct Entry {
+    name: String,
+    path: PathBuf,
+    anchor: (i32, i32),
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", MANIFEST);
+    println!("cargo:rerun-if-changed={}", ENCODER);
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let manifest = fs::read_to_string(MANIFEST).unwrap();
+    let mut module = String::new();
+
+    for (index, line) in manifest.lines().enumerate() {
+        let entry = match parse_line(line) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => continue,
+            Err(message) => panic!("{}:{}: {}", MANIFEST, index + 1, message),
+        };
+        println!("cargo:rerun-if-changed={}", entry.path.display());
+
+        let (width, height, pixels) = convert(&entry.path);
+        let key = color_key(&pixels);
+        let pixels: Vec<u16> = pixels.iter().map(|pixel| pixel.or(key).unwrap()).collect();
+        let mut data = Vec::new();
+        rle::encode(&pixels, &mut data)
+            .unwrap_or_else(|error| panic!("{}: {:?}", entry.path.display(), error));
+        // 展開して元に戻ることを確かめてから使う
+        assert!(
+            rle::Decoder::new(&data).eq(pixels.iter().copied()),
+            "{}: failed to round-trip",
+            entry.path.display()
+        );
+
+        let rle_path = out_dir.join(format!("{}.rle", entry.name));
+        fs::write(&rle_path, data).unwrap();
+
+        writeln!(
+            module,
+            "pub const {}: Asset = Asset {{ data: include_bytes!({:?}), width: {}, height: {}, anchor: Point::new({}, {}), key: {:?} }};",
+            entry.name,
+            rle_path.display().to_string(),
+            width,
+            height,
+            entry.anchor.0,
+            entry.anchor.1,
+            key,
+        )
+        .unwrap();
+    }
+
+    fs::write(out_dir.join("assets.rs"), module).unwrap();
+}
+
+// 空行とコメントは読み飛ばす
+fn parse_line(line: &str) -> Result<Option<Entry>, String> {
+    let line = line.split('#').next().unwrap().trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() != 4 {
+        return Err(format!("expected `NAME path x y`, got `{}`", line));
+    }
+
+    let coordinate = |field: &str| field.parse::<i32>().map_err(|error| format!("{}: {}", field, error));
+
+    Ok(Some(Entry {
+        name: fields[0].to_string(),
+        path: Path::new(ASSETS_DIR).join(fields[1]),
+        anchor: (coordinate(fields[2])?, coordinate(fields[3])?),
+    }))
+}
+
+// 透明な画素があれば、どの画素にも使われていない色を選ぶ
+fn color_key(pixels: &[Option<u16>]) -> Option<u16> {
+    if pixels.iter().all(Option::is_some) {
+        return None;
+    }
+
+    let mut key = PREFERRED_KEY;
+    while pixels.contains(&Some(key)) {
+        key = key.wrapping_add(1);
+    }
+    Some(key)
+}
+
+// PNGを読んで、画素をRgb565に詰め直す。透明な画素はNoneにする
+fn convert(path: &Path) -> (u32, u32, Vec<Option<u16>>) {
+    let file = File::open(path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let (info, mut reader) = decoder.read_info().unwrap();
+    let mut buffer = vec![0; info.buffer_size()];
+    reader.next_frame(&mut buffer).unwrap();
+
+    let (color_type, _) = reader.output_color_type();
+    let channels = match color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::RGB => 3,
+        png::ColorType::RGBA => 4,
+        png::ColorType::Indexed => unreachable!("EXPAND turns indexed colors into RGB"),
+    };
+
+    let pixels = buffer
+        .chunks(channels)
+        .map(|pixel| {
+            let (r, g, b) = if channels < 3 {
+                (pixel[0], pixel[0], pixel[0])
+            } else {
+                (pixel[0], pixel[1], pixel[2])
+            };
+            // アルファがあるのは2チャンネルと4チャンネルのとき
+            if channels % 2 == 0 && pixel[channels - 1] < ALPHA_THRESHOLD {
+                return None;
+            }
+            Some((u16::from(r) >> 3) << 11 | (u16::from(g) >> 2) << 5 | u16::from(b) >> 3)
+        })
+        .collect();
+
+    (info.width, info.height, pixels)
+}
@@ -0,0 +1,49 @@
+use crate::helpers::{
+    screen,
+    theme::Theme,
+};
+use core::fmt::Write;
+use embedded_graphics::{
+    egrectangle, egtext,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitive_style, text_style,
+};
+use heapless::{consts::*, String};
+use models::clock::DateTime;
+
+const FONT_HEIGHT: i32 = 16;
+
+pub struct ClockView;
+
+impl ClockView {
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        time: &DateTime
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        // 時計表示エリアをクリアする
+        egrectangle!(
+            top_left = (screen::CLOCK_LEFT, 0),
+            bottom_right = (screen::PEDOMETER_LEFT - 1, screen::PROGRESS_TOP - 1),
+            style = primitive_style!(fill_color = theme.background)
+        )
+        .draw(display)?;
+
+        let mut textbuffer = String::<U8>::new();
+        write!(&mut textbuffer, "{:02}:{:02}", time.hour, time.minute).unwrap();
+
+        // 進み具合の上の縦中央に描画する
+        egtext!(
+            text = textbuffer.as_str(),
+            top_left = (screen::CLOCK_LEFT, (screen::PROGRESS_TOP - FONT_HEIGHT) / 2),
+            style = text_style!(font = theme.clock_font, text_color = theme.foreground)
+        )
+        .draw(display)?;
+
+        Ok(())
+    }
+}
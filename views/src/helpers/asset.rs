@@ -0,0 +1,164 @@
+use crate::helpers::{
+    rle::Decoder,
+    theme::Theme,
+};
+use embedded_graphics::{
+    image::{Image, ImageDimensions, IntoPixelIter},
+    pixelcolor::{raw::RawU16, Rgb565},
+    prelude::*,
+};
+
+// ビルド時にPNGから変換した画像。定数はsrc/assets/manifest.txtからbuild.rsが生成する
+#[derive(Debug, Clone, Copy)]
+pub struct Asset {
+    // helpers::rleで圧縮したRgb565
+    pub data: &'static [u8],
+    pub width: u32,
+    pub height: u32,
+    // 描画する位置
+    pub anchor: Point,
+    // 透明な画素の色。透明な画素が無ければNone
+    pub key: Option<u16>,
+}
+
+impl Asset {
+    pub fn image(&self) -> RleImage<'static> {
+        RleImage {
+            data: self.data,
+            width: self.width,
+            height: self.height,
+            key: self.key,
+            matte: None,
+            ink: None,
+        }
+    }
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+    // 描画位置に描く
+    pub fn draw<T>(&self, display: &mut T) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        self.draw_at(display, self.anchor)
+    }
+    // 描画位置を無視して指定した位置に描く。透明な画素は描かずに下の絵を残す
+    pub fn draw_at<T>(&self, display: &mut T, point: Point) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        Image::new(&self.image(), point).draw(display)?;
+        Ok(())
+    }
+    // 透明な画素を背景色で塗って描く。前に描いた絵を消しながら描き直せる
+    pub fn draw_over<T>(&self, display: &mut T, point: Point, background: Rgb565) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let image = RleImage {
+            matte: Some(background),
+            ..self.image()
+        };
+        Image::new(&image, point).draw(display)?;
+        Ok(())
+    }
+    // 黒い線と白い塗りの画像を画面の色で描く。暗い色の画面では白黒が入れ替わる。
+    // 透明な画素は背景色で塗る
+    pub fn draw_themed<T>(&self, display: &mut T, point: Point, theme: &Theme) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let image = RleImage {
+            matte: Some(theme.background),
+            ink: Some((theme.foreground, theme.background)),
+            ..self.image()
+        };
+        Image::new(&image, point).draw(display)?;
+        Ok(())
+    }
+}
+
+// 圧縮したままの画像。描画するときに1画素ずつ展開するので、展開先のバッファはいらない
+#[derive(Debug, Clone, Copy)]
+pub struct RleImage<'a> {
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    // この色の画素は透明として扱う
+    pub key: Option<u16>,
+    // 透明な画素を塗る色。Noneなら透明な画素は飛ばす
+    pub matte: Option<Rgb565>,
+    // 黒と白の画素を置き換える色。Noneなら画像の色のまま
+    pub ink: Option<(Rgb565, Rgb565)>,
+}
+
+impl ImageDimensions for RleImage<'_> {
+    fn width(&self) -> u32 {
+        self.width
+    }
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl<'a> IntoPixelIter<Rgb565> for &RleImage<'a> {
+    type PixelIterator = RleImagePixels<'a>;
+
+    fn pixel_iter(self) -> Self::PixelIterator {
+        RleImagePixels {
+            decoder: Decoder::new(self.data),
+            width: self.width,
+            key: self.key,
+            matte: self.matte,
+            ink: self.ink,
+            point: Point::zero(),
+            remaining: self.width * self.height,
+        }
+    }
+}
+
+// 左上から1行ずつ画素を返す
+pub struct RleImagePixels<'a> {
+    decoder: Decoder<'a>,
+    width: u32,
+    key: Option<u16>,
+    matte: Option<Rgb565>,
+    ink: Option<(Rgb565, Rgb565)>,
+    point: Point,
+    remaining: u32,
+}
+
+impl Iterator for RleImagePixels<'_> {
+    type Item = Pixel<Rgb565>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.remaining == 0 {
+                return None;
+            }
+            self.remaining -= 1;
+
+            let raw = self.decoder.next()?;
+            let point = self.point;
+
+            self.point.x += 1;
+            if self.point.x as u32 == self.width {
+                self.point = Point::new(0, self.point.y + 1);
+            }
+
+            if Some(raw) != self.key {
+                let color = match (self.ink, Rgb565::from(RawU16::new(raw))) {
+                    (Some((black, _)), Rgb565::BLACK) => black,
+                    (Some((_, white)), Rgb565::WHITE) => white,
+                    (_, color) => color,
+                };
+                return Some(Pixel(point, color));
+            }
+            if let Some(matte) = self.matte {
+                return Some(Pixel(point, matte));
+            }
+        }
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/assets.rs"));
@@ -0,0 +1,385 @@
+use crate::helpers::screen;
+use core::convert::Infallible;
+use embedded_graphics::{
+    image::{Image, ImageDimensions, IntoPixelIter},
+    pixelcolor::{raw::RawU16, Rgb565},
+    prelude::*,
+    primitives::{Circle, Line, Rectangle, Triangle},
+    style::{PrimitiveStyle, Styled},
+    DrawTarget,
+};
+
+// 描き終えた画素をまとめてディスプレイへ送る先
+pub trait FlushTarget {
+    type Error;
+    // 前に送り始めた画素を送り終えてから、areaの画素を左上から1行ずつ送り始める。
+    // 送り終わる前に戻ってよいので、次に呼ばれるまでpixelsは書き換えない。
+    // 送りやすいようにpixelsの中身を並べ替えてもよい
+    fn flush(&mut self, area: &Rectangle, pixels: &mut [u16]) -> Result<(), Self::Error>;
+}
+
+// 画面の一部をRAMの上で描いてから、帯ごとにまとめて送る。
+// バッファを2つ交互に使い、片方を送っている間にもう片方へ次の帯を描く
+pub struct Compositor<'a> {
+    buffers: [&'a mut [u16]; 2],
+    // 次に描くバッファ
+    current: usize,
+}
+
+impl<'a> Compositor<'a> {
+    pub fn new(front: &'a mut [u16], back: &'a mut [u16]) -> Compositor<'a> {
+        Compositor {
+            buffers: [front, back],
+            current: 0,
+        }
+    }
+    // 1つの帯に描ける行数
+    pub fn band_height(&self, area: &Rectangle) -> i32 {
+        let capacity = self.buffers[0].len().min(self.buffers[1].len());
+        (capacity / width(area) as usize) as i32
+    }
+    // areaを上から帯に分け、帯ごとに背景色で塗ってからdrawで描いて送る。
+    // drawは帯の数だけ呼ばれ、帯の外に描いた画素は捨てられる。
+    // 帯にかからない図形と画像はTileが描かずに飛ばすので、帯ごとに描き直してもページ全体を何度も描くことにはならない。
+    // 最後の帯は送り終わる前に戻るので、その間に他の処理を進められる
+    pub fn compose<T, F>(
+        &mut self,
+        target: &mut T,
+        area: &Rectangle,
+        background: Rgb565,
+        mut draw: F,
+    ) -> Result<(), T::Error>
+    where
+        T: FlushTarget,
+        F: FnMut(&mut Tile),
+    {
+        let band_height = self.band_height(area);
+        assert!(band_height > 0, "buffer is smaller than a line of the area");
+
+        let mut top = area.top_left.y;
+        while top <= area.bottom_right.y {
+            let bottom = (top + band_height - 1).min(area.bottom_right.y);
+            let band = Rectangle::new(
+                Point::new(area.top_left.x, top),
+                Point::new(area.bottom_right.x, bottom),
+            );
+            let length = (width(&band) * height(&band)) as usize;
+            let pixels = &mut self.buffers[self.current][..length];
+
+            let mut tile = Tile { area: band, pixels };
+            tile.clear(background).ok();
+            draw(&mut tile);
+
+            // 送っている間はこのバッファに触らず、もう片方に次の帯を描く
+            target.flush(&band, tile.pixels)?;
+            self.current = 1 - self.current;
+            top = bottom + 1;
+        }
+
+        Ok(())
+    }
+}
+
+// 帯1つ分の描画先。座標は画面と同じで、帯の外は描かない
+pub struct Tile<'a> {
+    pub area: Rectangle,
+    pub pixels: &'a mut [u16],
+}
+
+impl Tile<'_> {
+    // 左上と右下の画素で表した範囲が帯にかかるか
+    fn overlaps(&self, top_left: Point, bottom_right: Point) -> bool {
+        let area = &self.area;
+        top_left.x <= area.bottom_right.x
+            && bottom_right.x >= area.top_left.x
+            && top_left.y <= area.bottom_right.y
+            && bottom_right.y >= area.top_left.y
+    }
+    // 線の太さの分だけ広げて、図形が帯にかかるか
+    fn overlaps_styled<T: Dimensions>(&self, item: &Styled<T, PrimitiveStyle<Rgb565>>) -> bool {
+        let stroke_width = item.style.stroke_width.min(i32::MAX as u32) as i32;
+        let margin = Point::new(stroke_width, stroke_width);
+        self.overlaps(item.top_left() - margin, item.bottom_right() + margin)
+    }
+}
+
+impl DrawTarget<Rgb565> for Tile<'_> {
+    type Error = Infallible;
+
+    fn draw_pixel(&mut self, Pixel(point, color): Pixel<Rgb565>) -> Result<(), Self::Error> {
+        let area = &self.area;
+        if point.x < area.top_left.x
+            || point.x > area.bottom_right.x
+            || point.y < area.top_left.y
+            || point.y > area.bottom_right.y
+        {
+            return Ok(());
+        }
+
+        let index = (point.y - area.top_left.y) * width(area) + (point.x - area.top_left.x);
+        self.pixels[index as usize] = RawU16::from(color).into_inner();
+        Ok(())
+    }
+    fn size(&self) -> Size {
+        Size::new(screen::SCREEN_WIDTH as u32, screen::SCREEN_HEIGHT as u32)
+    }
+    fn clear(&mut self, color: Rgb565) -> Result<(), Self::Error> {
+        let raw = RawU16::from(color).into_inner();
+        for pixel in self.pixels.iter_mut() {
+            *pixel = raw;
+        }
+        Ok(())
+    }
+    // 線の無い塗りつぶしは、帯にかかる部分だけを1行ずつ塗る
+    fn draw_rectangle(&mut self, item: &Styled<Rectangle, PrimitiveStyle<Rgb565>>) -> Result<(), Self::Error> {
+        if !self.overlaps_styled(item) {
+            return Ok(());
+        }
+
+        let style = &item.style;
+        let fill_color = match style.fill_color {
+            Some(color) if style.stroke_color.is_none() || style.stroke_width == 0 => color,
+            _ => return self.draw_iter(item),
+        };
+
+        let area = self.area;
+        let left = item.primitive.top_left.x.max(area.top_left.x);
+        let right = item.primitive.bottom_right.x.min(area.bottom_right.x);
+        let top = item.primitive.top_left.y.max(area.top_left.y);
+        let bottom = item.primitive.bottom_right.y.min(area.bottom_right.y);
+        let raw = RawU16::from(fill_color).into_inner();
+        for y in top..=bottom {
+            let start = ((y - area.top_left.y) * width(&area) + (left - area.top_left.x)) as usize;
+            for pixel in self.pixels[start..=start + (right - left) as usize].iter_mut() {
+                *pixel = raw;
+            }
+        }
+        Ok(())
+    }
+    fn draw_line(&mut self, item: &Styled<Line, PrimitiveStyle<Rgb565>>) -> Result<(), Self::Error> {
+        if !self.overlaps_styled(item) {
+            return Ok(());
+        }
+        self.draw_iter(item)
+    }
+    fn draw_triangle(&mut self, item: &Styled<Triangle, PrimitiveStyle<Rgb565>>) -> Result<(), Self::Error> {
+        if !self.overlaps_styled(item) {
+            return Ok(());
+        }
+        self.draw_iter(item)
+    }
+    fn draw_circle(&mut self, item: &Styled<Circle, PrimitiveStyle<Rgb565>>) -> Result<(), Self::Error> {
+        if !self.overlaps_styled(item) {
+            return Ok(());
+        }
+        self.draw_iter(item)
+    }
+    // 帯にかからない画像は展開しない。画像は左上から1行ずつ画素を返すので、帯の下まで来たらやめる
+    fn draw_image<'a, 'b, I>(&mut self, item: &'a Image<'b, I, Rgb565>) -> Result<(), Self::Error>
+    where
+        &'b I: IntoPixelIter<Rgb565>,
+        I: ImageDimensions,
+    {
+        let size = item.size();
+        let bottom_right = item.top_left() + Point::new(size.width as i32 - 1, size.height as i32 - 1);
+        if size.width == 0 || size.height == 0 || !self.overlaps(item.top_left(), bottom_right) {
+            return Ok(());
+        }
+
+        let bottom = self.area.bottom_right.y;
+        for pixel in item.into_iter().take_while(|Pixel(point, _)| point.y <= bottom) {
+            self.draw_pixel(pixel)?;
+        }
+        Ok(())
+    }
+}
+
+fn width(area: &Rectangle) -> i32 {
+    area.bottom_right.x - area.top_left.x + 1
+}
+
+fn height(area: &Rectangle) -> i32 {
+    area.bottom_right.y - area.top_left.y + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::cell::Cell;
+    use embedded_graphics::{
+        egrectangle,
+        primitive_style,
+    };
+
+    // 送られた領域と画素をそのまま残しておく
+    #[derive(Default)]
+    struct RecordingTarget {
+        flushes: Vec<(Rectangle, Vec<u16>)>,
+    }
+
+    impl FlushTarget for RecordingTarget {
+        type Error = Infallible;
+
+        fn flush(&mut self, area: &Rectangle, pixels: &mut [u16]) -> Result<(), Self::Error> {
+            self.flushes.push((*area, pixels.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_compose_bands() {
+        let mut front = [0; 40];
+        let mut back = [0; 40];
+        let mut compositor = Compositor::new(&mut front, &mut back);
+        let mut target = RecordingTarget::default();
+        let area = Rectangle::new(Point::new(2, 10), Point::new(11, 14));
+        let mut draws = 0;
+
+        compositor
+            .compose(&mut target, &area, Rgb565::WHITE, |tile| {
+                draws += 1;
+                // 帯をまたぐ四角と、領域の外にはみ出す四角
+                egrectangle!(
+                    top_left = (2, 13),
+                    bottom_right = (3, 14),
+                    style = primitive_style!(fill_color = Rgb565::BLACK)
+                )
+                .draw(tile)
+                .ok();
+                egrectangle!(
+                    top_left = (0, 0),
+                    bottom_right = (2, 10),
+                    style = primitive_style!(fill_color = Rgb565::RED)
+                )
+                .draw(tile)
+                .ok();
+            })
+            .unwrap();
+
+        // 10画素幅で4行ずつの帯に分ける
+        assert_eq!(draws, 2);
+        assert_eq!(
+            target.flushes.iter().map(|(area, _)| *area).collect::<Vec<_>>(),
+            [
+                Rectangle::new(Point::new(2, 10), Point::new(11, 13)),
+                Rectangle::new(Point::new(2, 14), Point::new(11, 14)),
+            ]
+        );
+
+        let white = RawU16::from(Rgb565::WHITE).into_inner();
+        let black = RawU16::from(Rgb565::BLACK).into_inner();
+        let red = RawU16::from(Rgb565::RED).into_inner();
+        let (_, first) = &target.flushes[0];
+        let (_, second) = &target.flushes[1];
+
+        assert_eq!(first.len(), 40);
+        assert_eq!(first[0], red);
+        assert_eq!(first[1], white);
+        assert_eq!(first[30..33], [black, black, white]);
+        assert_eq!(second[..], [black, black, white, white, white, white, white, white, white, white]);
+    }
+
+    #[test]
+    fn test_compose_alternates_buffers() {
+        let mut front = [0; 4];
+        let mut back = [0; 4];
+        let mut compositor = Compositor::new(&mut front, &mut back);
+        let area = Rectangle::new(Point::new(0, 0), Point::new(3, 2));
+
+        // 送り先が前のバッファを書き換えても、次の帯は別のバッファに描く
+        struct Target {
+            previous: Option<*const u16>,
+        }
+        impl FlushTarget for Target {
+            type Error = Infallible;
+
+            fn flush(&mut self, _area: &Rectangle, pixels: &mut [u16]) -> Result<(), Self::Error> {
+                assert_ne!(self.previous, Some(pixels.as_ptr()));
+                self.previous = Some(pixels.as_ptr());
+                Ok(())
+            }
+        }
+
+        let mut target = Target { previous: None };
+        compositor.compose(&mut target, &area, Rgb565::WHITE, |_| {}).unwrap();
+        compositor.compose(&mut target, &area, Rgb565::WHITE, |_| {}).unwrap();
+    }
+
+    // 全部が同じ色で、取り出された画素の数を数える画像
+    struct CountingImage {
+        width: u32,
+        height: u32,
+        decoded: Cell<usize>,
+    }
+
+    impl ImageDimensions for CountingImage {
+        fn width(&self) -> u32 {
+            self.width
+        }
+        fn height(&self) -> u32 {
+            self.height
+        }
+    }
+
+    impl<'a> IntoPixelIter<Rgb565> for &'a CountingImage {
+        type PixelIterator = Box<dyn Iterator<Item = Pixel<Rgb565>> + 'a>;
+
+        fn pixel_iter(self) -> Self::PixelIterator {
+            let width = self.width as i32;
+            Box::new((0..(self.width * self.height) as i32).map(move |index| {
+                self.decoded.set(self.decoded.get() + 1);
+                Pixel(Point::new(index % width, index / width), Rgb565::BLUE)
+            }))
+        }
+    }
+
+    #[test]
+    fn test_compose_skips_outside_band() {
+        let mut front = [0; 40];
+        let mut back = [0; 40];
+        let mut compositor = Compositor::new(&mut front, &mut back);
+        let mut target = RecordingTarget::default();
+        let area = Rectangle::new(Point::new(0, 0), Point::new(9, 11));
+        let image = CountingImage {
+            width: 4,
+            height: 3,
+            decoded: Cell::new(0),
+        };
+        let mut decoded = Vec::new();
+
+        compositor
+            .compose(&mut target, &area, Rgb565::WHITE, |tile| {
+                image.decoded.set(0);
+                // 2行目から4行目までの画像と、9行目の四角
+                Image::new(&image, Point::new(2, 2)).draw(tile).ok();
+                egrectangle!(
+                    top_left = (0, 9),
+                    bottom_right = (9, 9),
+                    style = primitive_style!(fill_color = Rgb565::BLACK)
+                )
+                .draw(tile)
+                .ok();
+                decoded.push(image.decoded.get());
+            })
+            .unwrap();
+
+        // 最初の帯は帯の下の行の先頭で止め、最後の帯では画像を展開しない
+        assert_eq!(decoded, [9, 12, 0]);
+
+        let white = RawU16::from(Rgb565::WHITE).into_inner();
+        let black = RawU16::from(Rgb565::BLACK).into_inner();
+        let blue = RawU16::from(Rgb565::BLUE).into_inner();
+        let (_, first) = &target.flushes[0];
+        let (_, second) = &target.flushes[1];
+        let (_, third) = &target.flushes[2];
+
+        assert_eq!(first[20..27], [white, white, blue, blue, blue, blue, white]);
+        assert_eq!(first[30..37], [white, white, blue, blue, blue, blue, white]);
+        assert_eq!(second[0..7], [white, white, blue, blue, blue, blue, white]);
+        assert!(second[10..].iter().all(|&pixel| pixel == white));
+        assert!(third[..10].iter().all(|&pixel| pixel == white));
+        assert!(third[10..20].iter().all(|&pixel| pixel == black));
+        assert!(third[20..].iter().all(|&pixel| pixel == white));
+    }
+}
@@ -0,0 +1,63 @@
+// 描き直す範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Redraw<T> {
+    // 前に描いたものと同じなので描かなくてよい
+    None,
+    // 前に何を描いたか分からないので全体を描く
+    Full,
+    // 前に描いた値から変わった部分だけを描く
+    Partial(T),
+}
+
+// 前に描いた値を覚えておき、変わった部分だけを描き直すために使う
+pub struct DirtyTracker<T> {
+    pub drawn: Option<T>,
+}
+
+impl<T> Default for DirtyTracker<T> {
+    fn default() -> Self {
+        DirtyTracker::new()
+    }
+}
+
+impl<T> DirtyTracker<T> {
+    pub fn new() -> DirtyTracker<T> {
+        DirtyTracker { drawn: None }
+    }
+    // 他の描画で上書きされたら、次は全体を描き直す
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+}
+
+impl<T: Copy + PartialEq> DirtyTracker<T> {
+    // これから描く値を受け取って、どこまで描き直すかを返す
+    pub fn redraw(&mut self, value: T) -> Redraw<T> {
+        let redraw = match self.drawn {
+            None => Redraw::Full,
+            Some(drawn) if drawn == value => Redraw::None,
+            Some(drawn) => Redraw::Partial(drawn),
+        };
+
+        self.drawn = Some(value);
+        redraw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redraw() {
+        let mut tracker = DirtyTracker::new();
+
+        assert_eq!(tracker.redraw(1), Redraw::Full);
+        assert_eq!(tracker.redraw(1), Redraw::None);
+        assert_eq!(tracker.redraw(2), Redraw::Partial(1));
+
+        tracker.invalidate();
+
+        assert_eq!(tracker.redraw(2), Redraw::Full);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::helpers::{
+    asset::{self, Asset},
+    sprite::Frame,
+};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use models::needs::Mood;
+
+// 息をするときに上に動かす量。画像の上下には透明な余白があり、背景色で塗って描くので前のコマは描き直すだけで消える
+const BREATH_OFFSET: Point = Point::new(0, -2);
+
+#[allow(dead_code)]
+pub enum CharacterState {
+    Angry,
+    Away,
+    Eat,
+    Happy,
+    Play,
+    Shy,
+    Sleep,
+}
+
+pub fn get_character_asset(state: &CharacterState) -> Asset {
+    match state {
+        CharacterState::Angry => asset::CHARACTER_ANGRY,
+        CharacterState::Away => asset::CHARACTER_AWAY,
+        CharacterState::Eat => asset::CHARACTER_EAT,
+        CharacterState::Happy => asset::CHARACTER_HAPPY,
+        CharacterState::Play => asset::CHARACTER_PLAY,
+        CharacterState::Shy => asset::CHARACTER_SHY,
+        CharacterState::Sleep => asset::CHARACTER_SLEEP,
+    }
+}
+
+// 表情ごとの待機中のアニメーション。
+// 最初のコマはget_character_assetと同じ画像で、まばたきは目の部分だけを重ねる
+pub fn get_character_frames(state: &CharacterState) -> &'static [Frame] {
+    match state {
+        CharacterState::Angry => &ANGRY_FRAMES,
+        CharacterState::Away => &AWAY_FRAMES,
+        CharacterState::Eat => &EAT_FRAMES,
+        CharacterState::Happy => &HAPPY_FRAMES,
+        CharacterState::Play => &PLAY_FRAMES,
+        CharacterState::Shy => &SHY_FRAMES,
+        CharacterState::Sleep => &SLEEP_FRAMES,
+    }
+}
+
+const fn still(asset: Asset, duration_ms: u32) -> Frame {
+    Frame {
+        asset,
+        offset: Point::new(0, 0),
+        duration_ms,
+    }
+}
+
+const fn breath(asset: Asset, duration_ms: u32) -> Frame {
+    Frame {
+        offset: BREATH_OFFSET,
+        ..still(asset, duration_ms)
+    }
+}
+
+// 重ねる画像の描画位置はキャラクターの左上からの位置
+const fn overlay(asset: Asset, duration_ms: u32) -> Frame {
+    Frame {
+        offset: asset.anchor,
+        ..still(asset, duration_ms)
+    }
+}
+
+static ANGRY_FRAMES: [Frame; 4] = [
+    still(asset::CHARACTER_ANGRY, 1200),
+    breath(asset::CHARACTER_ANGRY, 1200),
+    still(asset::CHARACTER_ANGRY, 1200),
+    overlay(asset::CHARACTER_ANGRY_BLINK, 150),
+];
+static AWAY_FRAMES: [Frame; 2] = [still(asset::CHARACTER_AWAY, 1500), breath(asset::CHARACTER_AWAY, 1500)];
+static EAT_FRAMES: [Frame; 1] = [still(asset::CHARACTER_EAT, 1000)];
+static HAPPY_FRAMES: [Frame; 4] = [
+    still(asset::CHARACTER_HAPPY, 1200),
+    breath(asset::CHARACTER_HAPPY, 1200),
+    still(asset::CHARACTER_HAPPY, 1200),
+    overlay(asset::CHARACTER_HAPPY_BLINK, 150),
+];
+static PLAY_FRAMES: [Frame; 1] = [still(asset::CHARACTER_PLAY, 1000)];
+static SHY_FRAMES: [Frame; 2] = [still(asset::CHARACTER_SHY, 1500), breath(asset::CHARACTER_SHY, 1500)];
+// 寝ているときはゆっくり息をする
+static SLEEP_FRAMES: [Frame; 2] = [still(asset::CHARACTER_SLEEP, 2000), breath(asset::CHARACTER_SLEEP, 2000)];
+
+// 重ねて描く1枚の絵
+pub struct Layer {
+    pub asset: Asset,
+    pub point: Point,
+}
+
+impl Layer {
+    // 画像の描画位置に置く
+    pub fn new(asset: Asset) -> Layer {
+        Layer::at(asset, asset.anchor)
+    }
+    pub fn at(asset: Asset, point: Point) -> Layer {
+        Layer { asset, point }
+    }
+}
+
+// 背景から前景の順に並べた絵を重ねて描く。透明な画素は描かないので、下の層が見える
+pub fn draw_layers<T>(display: &mut T, layers: &[Layer]) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    for layer in layers {
+        layer.asset.draw_at(display, layer.point)?;
+    }
+    Ok(())
+}
+
+// 機嫌に応じてHomeで見せる表情
+pub fn get_mood_state(mood: &Mood) -> CharacterState {
+    match mood {
+        Mood::Angry => CharacterState::Angry,
+        Mood::Away => CharacterState::Away,
+        Mood::Happy => CharacterState::Happy,
+        Mood::Shy => CharacterState::Shy,
+        Mood::Sleep => CharacterState::Sleep,
+    }
+}
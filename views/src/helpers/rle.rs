@@ -0,0 +1,185 @@
+// パレット付きのランレングス圧縮。
+// build.rsからも読み込んで圧縮に使うので、embedded-graphicsには依存しない。
+//
+// 先頭にパレットの色数-1を1バイト、続けてパレットの色をリトルエンディアンのRgb565で並べる。
+// その後はパケットが並び、パケットの先頭1バイトの上位1ビットで種類を、下位7ビットで画素数-1を表す
+//   0nnnnnnn i       : パレットのi番目の色がn+1画素続く
+//   1nnnnnnn i0 i1.. : n+1画素分のパレットの番号がそのまま並ぶ
+
+pub const MAX_COLORS: usize = 256;
+// 1つのパケットで表せる画素数
+const MAX_PACKET_LENGTH: usize = 128;
+const LITERAL: u8 = 0x80;
+
+#[derive(Debug, PartialEq)]
+pub enum EncodeError {
+    // パレットに入りきらない色数の画像
+    TooManyColors,
+    Empty,
+}
+
+pub fn encode<E: Extend<u8>>(pixels: &[u16], out: &mut E) -> Result<(), EncodeError> {
+    let mut palette = [0u16; MAX_COLORS];
+    let mut colors = 0;
+
+    for pixel in pixels {
+        if !palette[..colors].contains(pixel) {
+            if colors == MAX_COLORS {
+                return Err(EncodeError::TooManyColors);
+            }
+            palette[colors] = *pixel;
+            colors += 1;
+        }
+    }
+    if colors == 0 {
+        return Err(EncodeError::Empty);
+    }
+
+    let palette = &palette[..colors];
+    out.extend(Some((colors - 1) as u8));
+    for color in palette {
+        out.extend(color.to_le_bytes().iter().copied());
+    }
+
+    let index = |pixel: &u16| palette.iter().position(|color| color == pixel).unwrap() as u8;
+    let mut position = 0;
+
+    while position < pixels.len() {
+        let run = run_length(&pixels[position..]);
+
+        if run > 1 {
+            out.extend([(run - 1) as u8, index(&pixels[position])].iter().copied());
+            position += run;
+            continue;
+        }
+
+        // 次に同じ色が続くところまでをそのまま並べる
+        let mut length = 1;
+        while length < MAX_PACKET_LENGTH
+            && position + length < pixels.len()
+            && run_length(&pixels[position + length..]) == 1
+        {
+            length += 1;
+        }
+
+        out.extend(Some(LITERAL | (length - 1) as u8));
+        out.extend(pixels[position..position + length].iter().map(index));
+        position += length;
+    }
+
+    Ok(())
+}
+
+fn run_length(pixels: &[u16]) -> usize {
+    pixels
+        .iter()
+        .take(MAX_PACKET_LENGTH)
+        .take_while(|pixel| **pixel == pixels[0])
+        .count()
+}
+
+// 圧縮したデータを先頭から展開して、画素の色を順に返す。
+// 壊れたデータを読んだら、そこで終わる
+pub struct Decoder<'a> {
+    palette: &'a [u8],
+    body: &'a [u8],
+    position: usize,
+    // 今のパケットの残りの画素数
+    remaining: usize,
+    literal: bool,
+    color: u16,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Decoder<'a> {
+        let colors = data.first().map_or(0, |colors| *colors as usize + 1);
+        let palette_end = (1 + colors * 2).min(data.len());
+
+        Decoder {
+            palette: &data[palette_end.min(1)..palette_end],
+            body: &data[palette_end..],
+            position: 0,
+            remaining: 0,
+            literal: false,
+            color: 0,
+        }
+    }
+    fn read_color(&mut self) -> Option<u16> {
+        let index = *self.body.get(self.position)? as usize;
+        self.position += 1;
+
+        let bytes = self.palette.get(index * 2..index * 2 + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+impl Iterator for Decoder<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        if self.remaining == 0 {
+            let header = *self.body.get(self.position)?;
+            self.position += 1;
+            self.remaining = (header & !LITERAL) as usize + 1;
+            self.literal = header & LITERAL != 0;
+
+            if !self.literal {
+                self.color = self.read_color()?;
+            }
+        }
+
+        self.remaining -= 1;
+        if self.literal {
+            self.color = self.read_color()?;
+        }
+        Some(self.color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(pixels: &[u16]) -> Vec<u8> {
+        let mut data = Vec::new();
+        encode(pixels, &mut data).unwrap();
+
+        assert_eq!(Decoder::new(&data).collect::<Vec<_>>(), pixels);
+        data
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(&[0xFFFF]);
+        round_trip(&[0x0000, 0xFFFF, 0xFFFF, 0x0000]);
+        // パケットの長さの上限をまたぐ
+        round_trip(&[0xFFFF; 300]);
+        round_trip(&(0..300).map(|index| (index % 200) as u16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_encode_runs() {
+        let data = round_trip(&[0x0000, 0x0000, 0x0000, 0xFFFF, 0x1234, 0x1234]);
+
+        assert_eq!(
+            data,
+            [2, 0x00, 0x00, 0xFF, 0xFF, 0x34, 0x12, 2, 0, LITERAL, 1, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let pixels = (0..=MAX_COLORS).map(|index| index as u16).collect::<Vec<_>>();
+
+        assert_eq!(encode(&pixels, &mut Vec::new()), Err(EncodeError::TooManyColors));
+        assert_eq!(encode(&[], &mut Vec::new()), Err(EncodeError::Empty));
+    }
+
+    #[test]
+    fn test_decode_broken() {
+        // パレットの外の番号と途中で切れたパケット
+        assert_eq!(Decoder::new(&[0, 0xFF, 0xFF, 1, 3]).collect::<Vec<_>>(), []);
+        assert_eq!(Decoder::new(&[0, 0xFF, 0xFF, LITERAL | 3, 0]).collect::<Vec<_>>(), [0xFFFF]);
+        assert_eq!(Decoder::new(&[]).count(), 0);
+    }
+}
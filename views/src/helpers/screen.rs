@@ -1,8 +1,10 @@
+use crate::helpers::theme::Theme;
 use embedded_graphics::{
     egrectangle,
     pixelcolor::Rgb565,
     prelude::*,
     primitive_style,
+    primitives::Rectangle,
 };
 
 pub const SCREEN_WIDTH: i32 = 320;
@@ -10,17 +12,25 @@ pub const SCREEN_HEIGHT: i32 = 240;
 pub const FONT_WIDTH: i32 = 24;
 pub const FONT_HEIGHT: i32 = 32;
 pub const STATUS_BAR_HEIGHT: i32 = 32;
-pub const BACKGROUND_COLOR: Rgb565 = Rgb565::WHITE;
-pub const FOREGROUND_COLOR: Rgb565 = Rgb565::BLACK;
+// ステータスバーを左からナビゲーション、時計、歩数に分ける
+pub const CLOCK_LEFT: i32 = 128;
+pub const PEDOMETER_LEFT: i32 = 192;
+// 時計の下に目標までの進み具合を表示する
+pub const PROGRESS_TOP: i32 = 24;
+// ステータスバーの下のページエリア
+pub const PAGE_AREA: Rectangle = Rectangle::new(
+    Point::new(0, STATUS_BAR_HEIGHT),
+    Point::new(SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
+);
 
-pub fn clear_screen<T>(display: &mut T) -> Result<(), T::Error>
+pub fn clear_screen<T>(display: &mut T, theme: &Theme) -> Result<(), T::Error>
 where
     T: DrawTarget<Rgb565>,
 {
     egrectangle!(
         top_left = (0, 0),
         bottom_right = (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
-        style = primitive_style!(fill_color = BACKGROUND_COLOR)
+        style = primitive_style!(fill_color = theme.background)
     )
     .draw(display)?;
     Ok(())
@@ -29,6 +39,7 @@ where
 // ページエリアのみクリアする
 pub fn clear_page<T>(
     display: &mut T,
+    theme: &Theme,
 ) -> Result<(), T::Error>
 where
     T: DrawTarget<Rgb565>,
@@ -36,7 +47,7 @@ where
     egrectangle!(
         top_left = (0, STATUS_BAR_HEIGHT),
         bottom_right = (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
-        style = primitive_style!(fill_color = BACKGROUND_COLOR)
+        style = primitive_style!(fill_color = theme.background)
     )
     .draw(display)?;
     Ok(())
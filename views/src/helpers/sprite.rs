@@ -0,0 +1,73 @@
+use crate::helpers::asset::Asset;
+use core::ptr;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+
+// アニメーションの1コマ。前のコマに重ねて描くので、変わる部分だけの画像でもよい
+pub struct Frame {
+    pub asset: Asset,
+    // 基準の位置からずらして描く量
+    pub offset: Point,
+    // このコマを見せる時間(ミリ秒)
+    pub duration_ms: u32,
+}
+
+// コマを順に見せて、最後まで行ったら最初に戻るアニメーション
+pub struct AnimatedSprite {
+    pub frames: &'static [Frame],
+    pub index: usize,
+    // 今のコマを見せ始めてからの時間
+    pub elapsed_ms: u32,
+}
+
+impl AnimatedSprite {
+    pub fn new(frames: &'static [Frame]) -> AnimatedSprite {
+        AnimatedSprite {
+            frames,
+            index: 0,
+            elapsed_ms: 0,
+        }
+    }
+    // 別のアニメーションに切り替えたら最初のコマから見せる
+    pub fn play(&mut self, frames: &'static [Frame]) {
+        if !ptr::eq(self.frames, frames) {
+            self.frames = frames;
+            self.reset();
+        }
+    }
+    pub fn reset(&mut self) {
+        self.index = 0;
+        self.elapsed_ms = 0;
+    }
+    pub fn frame(&self) -> &Frame {
+        &self.frames[self.index]
+    }
+    // 経過時間を受け取って、コマが進んだらtrueを返す
+    pub fn tick(&mut self, dt_ms: u32) -> bool {
+        let index = self.index;
+        self.elapsed_ms += dt_ms;
+
+        while self.elapsed_ms >= self.frame().duration_ms {
+            self.elapsed_ms -= self.frame().duration_ms;
+            self.index = (self.index + 1) % self.frames.len();
+        }
+
+        self.index != index
+    }
+    // 透明な画素は背景色で塗り、ずれて描いた前のコマを消す
+    pub fn draw<T>(
+        &self,
+        display: &mut T,
+        point: Point,
+        background: Rgb565,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let frame = self.frame();
+
+        frame.asset.draw_over(display, point + frame.offset, background)
+    }
+}
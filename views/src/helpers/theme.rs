@@ -0,0 +1,50 @@
+use embedded_graphics::{
+    fonts::{Font12x16, Font24x32, Font6x8, Font8x16},
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+
+// 画面の色と文字。ビューはこれを受け取って描画する
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub background: Rgb565,
+    pub foreground: Rgb565,
+    // 目立たせたいもの。進み具合や今日の歩数の棒
+    pub accent: Rgb565,
+    // 気をつけてほしいもの。持っているアイテムが無いときの個数
+    pub warning: Rgb565,
+    // 見出しや大きな数字
+    pub title_font: Font24x32,
+    pub clock_font: Font12x16,
+    pub body_font: Font8x16,
+    pub small_font: Font6x8,
+}
+
+pub const LIGHT: Theme = Theme {
+    background: Rgb565::WHITE,
+    foreground: Rgb565::BLACK,
+    accent: Rgb565::new(2, 28, 24),
+    warning: Rgb565::new(26, 8, 4),
+    title_font: Font24x32,
+    clock_font: Font12x16,
+    body_font: Font8x16,
+    small_font: Font6x8,
+};
+
+// 夜でもまぶしくないように、黒地に明るい色で描く
+pub const DARK: Theme = Theme {
+    background: Rgb565::BLACK,
+    foreground: Rgb565::WHITE,
+    accent: Rgb565::new(10, 52, 28),
+    warning: Rgb565::new(31, 28, 6),
+    ..LIGHT
+};
+
+// Game::darkに合わせた色
+pub fn get_theme(dark: bool) -> &'static Theme {
+    if dark {
+        &DARK
+    } else {
+        &LIGHT
+    }
+}
@@ -0,0 +1,27 @@
+#![cfg_attr(not(test), no_std)]
+
+pub mod helpers {
+    pub mod asset;
+    pub mod compositor;
+    pub mod dirty;
+    pub mod image;
+    pub mod rle;
+    pub mod screen;
+    pub mod sprite;
+    pub mod theme;
+}
+pub mod clock_view;
+pub mod navigation_view;
+pub mod pedometer_view;
+pub mod progress_view;
+pub mod pages {
+    pub mod eat_page;
+    pub mod fail_page;
+    pub mod game_page;
+    pub mod home_page;
+    pub mod meal_page;
+    pub mod play_page;
+    pub mod stats_page;
+    pub mod success_page;
+    pub mod throw_page;
+}
@@ -0,0 +1,45 @@
+use crate::helpers::{
+    asset,
+    screen,
+    theme::Theme,
+};
+use models::router::Route;
+
+use embedded_graphics::{
+    egrectangle,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitive_style,
+};
+
+pub struct NavigationView;
+
+impl NavigationView {
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        focus: Route
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        // ナビゲーション表示エリアをクリアする
+        egrectangle!(
+            top_left = (0, 0),
+            bottom_right = (screen::CLOCK_LEFT - 1, screen::STATUS_BAR_HEIGHT - 1),
+            style = primitive_style!(fill_color = theme.background)
+        )
+        .draw(display)?;
+
+        // アイコンはそれぞれ自分の位置に描画される
+        let icon = match focus {
+            Route::Home => asset::NAVIGATION_HOME,
+            Route::Meal => asset::NAVIGATION_MEAL,
+            Route::Play => asset::NAVIGATION_PLAY,
+            Route::Game => asset::NAVIGATION_PLAY,
+            Route::Stats => asset::NAVIGATION_STATS,
+        };
+
+        icon.draw_themed(display, icon.anchor, theme)
+    }
+}
\ No newline at end of file
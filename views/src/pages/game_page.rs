@@ -0,0 +1,87 @@
+use models::target::TargetPosition;
+use crate::helpers::{
+    asset::{self, Asset},
+    dirty::{DirtyTracker, Redraw},
+    screen,
+    theme::Theme,
+};
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+
+// 左から並べた的の位置
+const CELLS: [TargetPosition; 3] = [
+    TargetPosition::Left,
+    TargetPosition::Center,
+    TargetPosition::Right,
+];
+
+pub struct GamePage;
+
+impl GamePage {
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        target_position: &TargetPosition
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        screen::clear_page(display, theme)?;
+
+        for cell in CELLS.iter() {
+            render_cell(display, theme, cell, target_position)?;
+        }
+
+        Ok(())
+    }
+    // 前に描いた位置を覚えておき、手裏剣が移った前後の的だけを描き直す
+    pub fn render_dirty<T>(
+        display: &mut T,
+        theme: &Theme,
+        tracker: &mut DirtyTracker<TargetPosition>,
+        target_position: &TargetPosition
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        match tracker.redraw(*target_position) {
+            Redraw::None => Ok(()),
+            Redraw::Full => GamePage::render(display, theme, target_position),
+            Redraw::Partial(previous) => {
+                render_cell(display, theme, &previous, target_position)?;
+                render_cell(display, theme, target_position, target_position)
+            }
+        }
+    }
+}
+
+// 的を1つ描く。手裏剣のある的、中央の的、両端の空の的で絵が違う
+fn render_cell<T>(
+    display: &mut T,
+    theme: &Theme,
+    cell: &TargetPosition,
+    target_position: &TargetPosition
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let image = if cell == target_position {
+        asset::GAME_SHURIKEN
+    } else if *cell == TargetPosition::Center {
+        asset::GAME_TARGET
+    } else {
+        asset::GAME_EMPTY_TARGET
+    };
+
+    // 前に描いた的を消しながら描く
+    image.draw_themed(display, cell_point(cell, &image), theme)
+}
+
+// 的は同じ大きさで、ステータスバーの下に左から並べる
+fn cell_point(cell: &TargetPosition, image: &Asset) -> Point {
+    let index = CELLS.iter().position(|position| position == cell).unwrap() as i32;
+
+    Point::new(index * image.width as i32, screen::STATUS_BAR_HEIGHT)
+}
@@ -0,0 +1,45 @@
+use crate::helpers::{
+    image,
+    screen,
+    sprite::AnimatedSprite,
+    theme::Theme,
+};
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+use models::needs::Mood;
+
+pub struct HomePage;
+
+impl HomePage {
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        mood: &Mood,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        screen::clear_page(display, theme)?;
+
+        let state = image::get_mood_state(mood);
+        image::get_character_asset(&state).draw(display)?;
+        Ok(())
+    }
+    // 待機中のアニメーションの今のコマを、前のコマに重ねて描画する
+    pub fn render_sprite<T>(
+        display: &mut T,
+        theme: &Theme,
+        mood: &Mood,
+        sprite: &AnimatedSprite,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let state = image::get_mood_state(mood);
+        let point = image::get_character_asset(&state).anchor;
+
+        sprite.draw(display, point, theme.background)
+    }
+}
\ No newline at end of file
@@ -1,9 +1,11 @@
-use crate::helpers::screen;
-use crate::models::{meal::Meal, rice_ball::RiceBall};
+use crate::helpers::{
+    screen,
+    theme::Theme,
+};
+use models::{meal::Meal, rice_ball::RiceBall};
 use core::fmt::Write;
 use embedded_graphics::{
     egtext,
-    fonts::Font24x32,
     pixelcolor::Rgb565,
     prelude::*,
     text_style,
@@ -15,18 +17,19 @@ pub struct MealPage;
 impl MealPage {
     pub fn render<T>(
         display: &mut T,
+        theme: &Theme,
         rice_ball: &RiceBall,
         meal: &Meal,
     ) -> Result<(), T::Error>
     where
         T: DrawTarget<Rgb565>,
     {
-        screen::clear_page(display)?;
+        screen::clear_page(display, theme)?;
 
         egtext!(
             text = "How many ?",
             top_left = (0, screen::STATUS_BAR_HEIGHT),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = theme.foreground)
         ).draw(display)?;
 
 
@@ -37,18 +40,19 @@ impl MealPage {
         egtext!(
             text = meal_amount_textbuffer.as_str(),
             top_left = (0, screen::STATUS_BAR_HEIGHT + screen::FONT_HEIGHT),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = theme.foreground)
         )
         .draw(display)?;
 
-        // 持っているおにぎりの個数を描画する
+        // 持っているおにぎりの個数を描画する。1つも無ければ注意の色にする
+        let stock_color = if rice_ball.amount > 0 { theme.foreground } else { theme.warning };
         let mut rice_ball_amount_textbuffer = String::<U256>::new();
         write!(&mut rice_ball_amount_textbuffer, "/{:.2}", rice_ball.amount).unwrap();
 
         egtext!(
             text = rice_ball_amount_textbuffer.as_str(),
             top_left = (0, screen::STATUS_BAR_HEIGHT + screen::FONT_HEIGHT * 2),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = stock_color)
         )
         .draw(display)?;
 
@@ -1,9 +1,11 @@
-use crate::helpers::screen;
-use crate::models::{bet::Bet, shuriken::Shuriken};
+use crate::helpers::{
+    screen,
+    theme::Theme,
+};
+use models::{bet::Bet, shuriken::Shuriken};
 use core::fmt::Write;
 use embedded_graphics::{
     egtext,
-    fonts::Font24x32,
     pixelcolor::Rgb565,
     prelude::*,
     text_style,
@@ -15,18 +17,19 @@ pub struct PlayPage;
 impl PlayPage {
     pub fn render<T>(
         display: &mut T,
+        theme: &Theme,
         bet: &Bet,
         shuriken: &Shuriken,
     ) -> Result<(), T::Error>
     where
         T: DrawTarget<Rgb565>,
     {
-        screen::clear_page(display)?;
+        screen::clear_page(display, theme)?;
 
         egtext!(
             text = "How much ?",
             top_left = (0, screen::STATUS_BAR_HEIGHT),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = theme.foreground)
         ).draw(display)?;
 
         // BETする手裏剣の個数を描画する
@@ -36,18 +39,19 @@ impl PlayPage {
         egtext!(
             text = bet_amount_textbuffer.as_str(),
             top_left = (0, screen::STATUS_BAR_HEIGHT + screen::FONT_HEIGHT),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = theme.foreground)
         )
         .draw(display)?;
 
-        // 持っている手裏剣の個数を描画する
+        // 持っている手裏剣の個数を描画する。1つも無ければ注意の色にする
+        let stock_color = if shuriken.amount > 0 { theme.foreground } else { theme.warning };
         let mut shuriken_amount_textbuffer = String::<U256>::new();
         write!(&mut shuriken_amount_textbuffer, "/{:.2}", shuriken.amount).unwrap();
 
         egtext!(
             text = shuriken_amount_textbuffer.as_str(),
             top_left = (0, screen::STATUS_BAR_HEIGHT + screen::FONT_HEIGHT * 2),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = stock_color)
         )
         .draw(display)?;
 
@@ -0,0 +1,265 @@
+use crate::helpers::{
+    screen,
+    theme::Theme,
+};
+use models::{
+    activity::{
+        Activity,
+        ActivityClassifier,
+    },
+    clock::DateTime,
+    game::Game,
+    pedometer::{
+        DetectorKind,
+        DAYS_OF_HISTORY,
+    },
+    profile::{
+        Exercise,
+        Profile,
+    },
+    settings::{
+        SettingItem,
+        Settings,
+        ThemeMode,
+        MAX_BRIGHTNESS,
+    },
+};
+use core::fmt::Write;
+use embedded_graphics::{
+    egline, egrectangle, egtext,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitive_style, text_style,
+};
+use heapless::{consts::*, String};
+
+// 1日分の棒グラフの幅と、棒の幅
+const SLOT_WIDTH: i32 = screen::SCREEN_WIDTH / DAYS_OF_HISTORY as i32;
+const BAR_WIDTH: i32 = 29;
+// 7日分を画面の中央に寄せる
+const CHART_LEFT: i32 = (screen::SCREEN_WIDTH - SLOT_WIDTH * DAYS_OF_HISTORY as i32) / 2;
+const CHART_TOP: i32 = 80;
+const CHART_BOTTOM: i32 = 216;
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+// 今日以外の棒の色
+const PAST_BAR_COLOR: Rgb565 = Rgb565::new(16, 32, 16);
+// タイトルの右に選んでいる設定を描く
+const SETTING_LEFT: i32 = 128;
+
+// Statsページに描くもの
+pub struct StatsModel<'a> {
+    // 古い順に並んだ日ごとの歩数。最後が今日になる
+    pub days: [i32; DAYS_OF_HISTORY],
+    pub today: &'a DateTime,
+    pub classifier: &'a ActivityClassifier,
+    pub exercise: &'a Exercise,
+    pub settings: &'a Settings,
+    // 1日の目標歩数
+    pub goal_step_count: i32,
+    pub profile: &'a Profile,
+}
+
+impl<'a> StatsModel<'a> {
+    pub fn from_game(game: &'a Game) -> StatsModel<'a> {
+        StatsModel {
+            days: game.pedometer.history.days.totals(),
+            today: &game.time,
+            classifier: &game.classifier,
+            exercise: &game.exercise,
+            settings: &game.settings,
+            goal_step_count: game.goals.step_count(),
+            profile: &game.profile,
+        }
+    }
+}
+
+pub struct StatsPage;
+
+impl StatsPage {
+    // 日ごとの歩数を棒グラフで描画する
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        stats: &StatsModel,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let StatsModel {
+            days,
+            today,
+            classifier,
+            exercise,
+            settings,
+            goal_step_count,
+            profile,
+        } = stats;
+
+        screen::clear_page(display, theme)?;
+
+        egtext!(
+            text = "Steps",
+            top_left = (0, screen::STATUS_BAR_HEIGHT),
+            style = text_style!(font = theme.title_font, text_color = theme.foreground)
+        )
+        .draw(display)?;
+
+        // 押して選び、上下のボタンで切り替える設定を描画する
+        let mut value_textbuffer = String::<U8>::new();
+        let label = match settings.selected {
+            SettingItem::Theme => {
+                let theme_mode = match settings.theme {
+                    ThemeMode::Light => "Light",
+                    ThemeMode::Dark => "Dark",
+                    ThemeMode::Auto => "Auto",
+                };
+                value_textbuffer.push_str(theme_mode).unwrap();
+                "Theme"
+            }
+            SettingItem::Brightness => {
+                write!(&mut value_textbuffer, "{}/{}", settings.brightness, MAX_BRIGHTNESS).unwrap();
+                "Backlight"
+            }
+            SettingItem::Hour | SettingItem::Minute => {
+                write!(&mut value_textbuffer, "{:02}:{:02}", today.hour, today.minute).unwrap();
+                if settings.selected == SettingItem::Hour { "Hour" } else { "Minute" }
+            }
+            SettingItem::Goal => {
+                write!(&mut value_textbuffer, "{}", goal_step_count).unwrap();
+                "Goal"
+            }
+            SettingItem::Height => {
+                write!(&mut value_textbuffer, "{}cm", profile.height_cm).unwrap();
+                "Height"
+            }
+            SettingItem::Weight => {
+                write!(&mut value_textbuffer, "{}kg", profile.weight_kg).unwrap();
+                "Weight"
+            }
+            // 測っていなければ身長から求める
+            SettingItem::Stride => {
+                match profile.stride_cm {
+                    Some(stride_cm) => write!(&mut value_textbuffer, "{}cm", stride_cm).unwrap(),
+                    None => value_textbuffer.push_str("Auto").unwrap(),
+                }
+                "Stride"
+            }
+            SettingItem::Detector => {
+                let detector = match settings.detector {
+                    DetectorKind::Threshold => "Threshold",
+                    DetectorKind::Peak => "Peak",
+                };
+                value_textbuffer.push_str(detector).unwrap();
+                "Counter"
+            }
+        };
+
+        egtext!(
+            text = label,
+            top_left = (SETTING_LEFT, screen::STATUS_BAR_HEIGHT),
+            style = text_style!(font = theme.body_font, text_color = theme.foreground)
+        )
+        .draw(display)?;
+        egtext!(
+            text = value_textbuffer.as_str(),
+            top_left = (SETTING_LEFT, screen::STATUS_BAR_HEIGHT + 16),
+            style = text_style!(font = theme.body_font, text_color = theme.accent)
+        )
+        .draw(display)?;
+
+        // 右端に今日歩いた距離と消費カロリーを描画する
+        let distance_m = exercise.distance_m();
+        let mut exercise_textbuffer = String::<U32>::new();
+        write!(
+            &mut exercise_textbuffer,
+            "{}.{:02}km {}kcal",
+            distance_m / 1000,
+            distance_m % 1000 / 10,
+            exercise.kilocalories()
+        )
+        .unwrap();
+
+        egtext!(
+            text = exercise_textbuffer.as_str(),
+            top_left = (
+                screen::SCREEN_WIDTH - exercise_textbuffer.len() as i32 * 8,
+                screen::STATUS_BAR_HEIGHT
+            ),
+            style = text_style!(font = theme.body_font, text_color = theme.foreground)
+        )
+        .draw(display)?;
+
+        // その下に今していることとケイデンスを描画する
+        let activity = match classifier.activity {
+            Activity::Still => "Still",
+            Activity::Walking => "Walking",
+            Activity::Running => "Running",
+        };
+        let mut activity_textbuffer = String::<U16>::new();
+        write!(&mut activity_textbuffer, "{} {}/min", activity, classifier.cadence).unwrap();
+
+        egtext!(
+            text = activity_textbuffer.as_str(),
+            top_left = (
+                screen::SCREEN_WIDTH - activity_textbuffer.len() as i32 * 8,
+                screen::STATUS_BAR_HEIGHT + 16
+            ),
+            style = text_style!(font = theme.body_font, text_color = theme.foreground)
+        )
+        .draw(display)?;
+
+        // 一番多い日がグラフの一番上になるようにする
+        let max = days.iter().copied().max().unwrap_or(0).max(1);
+
+        for (index, total) in days.iter().enumerate() {
+            let left = CHART_LEFT + SLOT_WIDTH * index as i32;
+            let center = left + SLOT_WIDTH / 2;
+            let height = total * (CHART_BOTTOM - CHART_TOP) / max;
+            let is_today = index == DAYS_OF_HISTORY - 1;
+            let color = if is_today { theme.accent } else { PAST_BAR_COLOR };
+
+            if height > 0 {
+                egrectangle!(
+                    top_left = (center - BAR_WIDTH / 2, CHART_BOTTOM - height),
+                    bottom_right = (center + BAR_WIDTH / 2, CHART_BOTTOM - 1),
+                    style = primitive_style!(fill_color = color)
+                )
+                .draw(display)?;
+            }
+
+            // 棒の上に歩数を描画する
+            let mut total_textbuffer = String::<U16>::new();
+            write!(&mut total_textbuffer, "{}", total).unwrap();
+
+            egtext!(
+                text = total_textbuffer.as_str(),
+                top_left = (center - total_textbuffer.len() as i32 * 6 / 2, CHART_BOTTOM - height - 10),
+                style = text_style!(font = theme.small_font, text_color = theme.foreground)
+            )
+            .draw(display)?;
+
+            // 棒の下に日付を描画する
+            let days_ago = (DAYS_OF_HISTORY - 1 - index) as u32;
+            let date = DateTime::from_seconds(today.seconds().saturating_sub(days_ago * SECONDS_PER_DAY));
+            let mut day_textbuffer = String::<U4>::new();
+            write!(&mut day_textbuffer, "{}", date.day).unwrap();
+
+            egtext!(
+                text = day_textbuffer.as_str(),
+                top_left = (center - day_textbuffer.len() as i32 * 8 / 2, CHART_BOTTOM + 6),
+                style = text_style!(font = theme.body_font, text_color = theme.foreground)
+            )
+            .draw(display)?;
+        }
+
+        // グラフの底の線を描画する
+        egline!(
+            start = (CHART_LEFT, CHART_BOTTOM),
+            end = (screen::SCREEN_WIDTH - 1 - CHART_LEFT, CHART_BOTTOM),
+            style = primitive_style!(stroke_color = theme.foreground, stroke_width = 1)
+        )
+        .draw(display)?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,9 @@
 use crate::helpers::{
     image,
     screen,
+    theme::Theme,
 };
 use embedded_graphics::{
-    image::Image,
     pixelcolor::Rgb565,
     prelude::*,
 };
@@ -12,18 +12,16 @@ pub struct SuccessPage;
 
 impl SuccessPage {
     pub fn render<T>(
-        display: &mut T
+        display: &mut T,
+        theme: &Theme,
     ) -> Result<(), T::Error>
     where
         T: DrawTarget<Rgb565>,
     {
-        screen::clear_page(display)?;
+        screen::clear_page(display, theme)?;
 
         let state = image::CharacterState::Happy;
-        let data = image::get_character_data(&state);
-        let point = image::get_character_point(&state);
-
-        Image::new(&data, point).draw(display)?;
+        image::get_character_asset(&state).draw(display)?;
 
         Ok(())
     }
@@ -0,0 +1,68 @@
+use crate::helpers::{
+    asset,
+    image::{self, Layer},
+    screen,
+    theme::Theme,
+};
+use embedded_graphics::{
+    egrectangle,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitive_style,
+};
+
+// 手裏剣を投げ始める位置と、1コマで進む距離
+const SHURIKEN_LEFT: i32 = 180;
+const SHURIKEN_TOP: i32 = 100;
+const SHURIKEN_SPEED: i32 = 16;
+
+pub struct ThrowPage;
+
+impl ThrowPage {
+    // 最初のコマでキャラクターを描画し、以降のコマでは手裏剣だけを動かす
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        frame: u32,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if frame == 0 {
+            screen::clear_page(display, theme)?;
+
+            // 手裏剣を構えたキャラクターを重ねて描く
+            let state = image::CharacterState::Play;
+            return image::draw_layers(display, &[
+                Layer::new(image::get_character_asset(&state)),
+                Layer::at(asset::GAME_SHURIKEN, Point::new(shuriken_left(frame), SHURIKEN_TOP)),
+            ]);
+        }
+        if is_visible(frame - 1) {
+            // 前のコマの手裏剣を消す
+            let left = shuriken_left(frame - 1);
+            let size = asset::GAME_SHURIKEN.size();
+            egrectangle!(
+                top_left = (left, SHURIKEN_TOP),
+                bottom_right = (left + size.width as i32 - 1, SHURIKEN_TOP + size.height as i32 - 1),
+                style = primitive_style!(fill_color = theme.background)
+            )
+            .draw(display)?;
+        }
+
+        // 画面の外に出たら描画しない
+        if !is_visible(frame) {
+            return Ok(());
+        }
+
+        asset::GAME_SHURIKEN.draw_at(display, Point::new(shuriken_left(frame), SHURIKEN_TOP))
+    }
+}
+
+fn shuriken_left(frame: u32) -> i32 {
+    SHURIKEN_LEFT + SHURIKEN_SPEED * frame as i32
+}
+
+fn is_visible(frame: u32) -> bool {
+    shuriken_left(frame) + asset::GAME_SHURIKEN.width as i32 <= screen::SCREEN_WIDTH
+}
@@ -1,8 +1,11 @@
-use crate::helpers::screen;
+use crate::helpers::{
+    dirty::{DirtyTracker, Redraw},
+    screen,
+    theme::Theme,
+};
 use core::fmt::Write;
 use embedded_graphics::{
     egrectangle, egtext,
-    fonts::Font24x32,
     pixelcolor::Rgb565,
     prelude::*,
     primitive_style, text_style,
@@ -14,16 +17,17 @@ pub struct PedometerView;
 impl PedometerView {
     pub fn render<T>(
         display: &mut T,
-        step_count: &mut i32
+        theme: &Theme,
+        step_count: &i32
     ) -> Result<(), T::Error>
     where
         T: DrawTarget<Rgb565>,
     {
         // カウント表示エリアをクリアする
         egrectangle!(
-            top_left = (screen::SCREEN_WIDTH / 2, 0),
-            bottom_right = (screen::SCREEN_WIDTH - 1, screen::FONT_HEIGHT),
-            style = primitive_style!(fill_color = screen::BACKGROUND_COLOR)
+            top_left = (screen::PEDOMETER_LEFT, 0),
+            bottom_right = (screen::SCREEN_WIDTH - 1, screen::STATUS_BAR_HEIGHT - 1),
+            style = primitive_style!(fill_color = theme.background)
         )
         .draw(display)?;
 
@@ -40,10 +44,25 @@ impl PedometerView {
         egtext!(
             text = textbuffer.as_str(),
             top_left = (left, 0),
-            style = text_style!(font = Font24x32, text_color = screen::FOREGROUND_COLOR)
+            style = text_style!(font = theme.title_font, text_color = theme.foreground)
         )
         .draw(display)?;
 
         Ok(())
     }
+    // 前に描いた歩数から変わったときだけ描き直す
+    pub fn render_dirty<T>(
+        display: &mut T,
+        theme: &Theme,
+        tracker: &mut DirtyTracker<i32>,
+        step_count: &i32
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        match tracker.redraw(*step_count) {
+            Redraw::None => Ok(()),
+            Redraw::Full | Redraw::Partial(_) => PedometerView::render(display, theme, step_count),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use crate::helpers::{
+    screen,
+    theme::Theme,
+};
+use embedded_graphics::{
+    egrectangle,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitive_style,
+};
+
+// 枠の内側の余白
+const MARGIN: i32 = 2;
+
+pub struct ProgressView;
+
+impl ProgressView {
+    // 次の目標までの進み具合をバーで描画する
+    pub fn render<T>(
+        display: &mut T,
+        theme: &Theme,
+        progress: &(i32, i32),
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let left = screen::CLOCK_LEFT + MARGIN;
+        let right = screen::PEDOMETER_LEFT - 1 - MARGIN;
+        let top = screen::PROGRESS_TOP + MARGIN;
+        let bottom = screen::STATUS_BAR_HEIGHT - 1 - MARGIN;
+
+        // 進み具合表示エリアをクリアする
+        egrectangle!(
+            top_left = (screen::CLOCK_LEFT, screen::PROGRESS_TOP),
+            bottom_right = (screen::PEDOMETER_LEFT - 1, screen::STATUS_BAR_HEIGHT - 1),
+            style = primitive_style!(fill_color = theme.background)
+        )
+        .draw(display)?;
+
+        // 枠を描画する
+        egrectangle!(
+            top_left = (left, top),
+            bottom_right = (right, bottom),
+            style = primitive_style!(stroke_color = theme.foreground, stroke_width = 1)
+        )
+        .draw(display)?;
+
+        // 枠の内側を進んだ分だけ塗る
+        let (current, goal) = *progress;
+        let width = (right - left - 1) * current.max(0) / goal.max(1);
+
+        if width > 0 {
+            egrectangle!(
+                top_left = (left + 1, top + 1),
+                bottom_right = (left + width, bottom - 1),
+                style = primitive_style!(fill_color = theme.accent)
+            )
+            .draw(display)?;
+        }
+
+        Ok(())
+    }
+}